@@ -1,27 +1,83 @@
+use crate::{
+    data_objects::response,
+    services::{cache::CampaignCache, store::CampaignStore},
+};
+use std::{convert::Infallible, sync::Arc};
+use vercel_runtime as Vercel;
 use warp::{http::Method, Filter};
 
 pub mod create;
+pub mod create_preview;
 pub mod eligibility;
+pub mod eligibility_batch;
 pub mod health;
+pub mod multiproof;
 pub mod validity;
+pub mod verify;
 
 /// Handle the rejection raised by the Warp framework.
 async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, std::convert::Infallible> {
     Ok(warp::reply::json(&format!("{:?}", err)))
 }
 
+/// Bearer token guard
+pub(crate) fn is_authorized(req: &Vercel::Request) -> bool {
+    let headers = req.headers();
+    let expected_token = std::env::var("MERKLE_API_BEARER_TOKEN").expect("MERKLE_API_BEARER_TOKEN must be set");
+
+    if let Some(auth_header) = headers.get("Authorization") {
+        if let Ok(auth_str) = auth_header.to_str() {
+            return auth_str == format!("Bearer {}", expected_token);
+        }
+    }
+
+    false
+}
+
+/// Inject the shared campaign store into the Warp handlers.
+fn with_store(
+    store: Arc<dyn CampaignStore>,
+) -> impl warp::Filter<Extract = (Arc<dyn CampaignStore>,), Error = Infallible> + Clone {
+    warp::any().map(move || store.clone())
+}
+
+/// Inject the shared campaign cache into the Warp handlers.
+fn with_cache(
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = (Arc<CampaignCache>,), Error = Infallible> + Clone {
+    warp::any().map(move || cache.clone())
+}
+
 /// Binds all the routes into a single API and create a proper configuration with the allowed headers and CORS
 /// configuration.
-pub fn build_routes() -> impl warp::Filter<Extract = impl warp::Reply> + Clone {
+pub fn build_routes(
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = impl warp::Reply> + Clone {
     let cors = warp::cors()
         .allow_methods(&[Method::GET, Method::POST, Method::PATCH, Method::DELETE])
         .allow_any_origin()
-        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"]);
+        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"])
+        .expose_headers(vec!["Content-Disposition", response::MESSAGE_HEADER]);
 
-    let health = health::build_route();
-    let create = create::build_route();
-    let eligibility = eligibility::build_route();
-    let validity = validity::build_route();
+    let health = health::build_route(cache.clone());
+    let create = create::build_route(store.clone());
+    let create_preview = create_preview::build_route();
+    let eligibility = eligibility::build_route(store.clone(), cache.clone());
+    let eligibility_batch = eligibility_batch::build_route(store.clone(), cache.clone());
+    let multiproof = multiproof::build_route(store.clone(), cache.clone());
+    let validity = validity::build_route(store.clone(), cache.clone());
+    let verify = verify::build_route(store, cache);
 
-    health.or(eligibility).or(create).or(validity).recover(handle_rejection).with(cors).with(warp::log("api"))
+    health
+        .or(eligibility)
+        .or(eligibility_batch)
+        .or(create)
+        .or(create_preview)
+        .or(multiproof)
+        .or(validity)
+        .or(verify)
+        .recover(handle_rejection)
+        .with(cors)
+        .with(warp::log("api"))
 }
@@ -1,28 +1,140 @@
 use crate::{
-    csv_campaign_parser::CampaignCsvParsed,
+    csv_campaign_parser::{AddressChecksum, CampaignCsvParsed, Duplicates, InputFormat, ParserOptions},
+    csv_report::{parse_with_report, ErrorReport, ValidationReport},
     data_objects::{
-        dto::{PersistentCampaignDto, RecipientDto},
+        dto::{PersistentCampaignDto, RecipientDto, ShardedCampaignDto, CAMPAIGN_SCHEMA_VERSION},
         query_param::Create,
-        response::{self, GeneralErrorResponse, UploadSuccessResponse, ValidationErrorResponse},
+        response::{
+            self, Attachment, GeneralErrorResponse, UploadSuccessResponse, ValidationErrorResponse,
+            ValidationReportResponse,
+        },
+    },
+    services::{
+        cache::build_address_index,
+        store::{self, put_json, CampaignStore, StoreResult},
+    },
+    utils::{
+        campaign_rules::CampaignRules,
+        csv_validator::{ColumnAliases, ValidationError},
+        leaf_schema::LeafSchema,
+        merkle::MerkleTreeDump,
+        sharding::{build_shards, prefix_length_for, CampaignLayout, SHARDED_LAYOUT},
     },
-    services::ipfs::{try_deserialize_pinata_response, upload_to_ipfs},
     FormData, StreamExt, TryStreamExt, WebResult,
 };
 
-use csv::ReaderBuilder;
-use merkle_tree_rs::standard::StandardMerkleTree;
-use std::{collections::HashMap, io::Read, num::ParseIntError, str};
+use bytes::{Buf, Bytes};
+use futures::Stream;
+use multipart::server::Multipart;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    io::{self, Read},
+    pin::Pin,
+    str,
+    sync::Arc,
+};
+use tokio_util::io::{StreamReader, SyncIoBridge};
 use url::Url;
 
 use serde_json::json;
 use vercel_runtime as Vercel;
-use warp::{Buf, Filter};
+use warp::Filter;
+
+pub(crate) type ParserError = Box<dyn Error + Send + Sync>;
+
+/// Maximum size of the recipient list sent to the create endpoints, in bytes
+const MAX_BODY_LENGTH: u64 = 100_000_000;
+
+/// Name of the annotated CSV file sent back when a report is requested
+const REPORT_FILE_NAME: &str = "recipients-report.csv";
+
+type BodyStream = Pin<Box<dyn Stream<Item = Result<Bytes, warp::Error>> + Send>>;
+
+/// The recipient list of a Warp request, sent either as the `data` file of a multipart form or as a JSON or NDJSON body
+pub enum Recipients {
+    Form(FormData),
+    Body(InputFormat, BodyStream),
+}
+
+/// Create request common handler. It validates the received data, creates the merkle tree and uploads it to the
+/// campaign store. The CSV file is read row by row from the provided reader, unless an annotated report is requested.
+async fn handler<R: Read>(
+    options: ParserOptions,
+    layout: CampaignLayout,
+    report: ErrorReport,
+    reader: R,
+    store: &dyn CampaignStore,
+) -> response::R {
+    upload_or_report(parse(reader, &options, report), &options.leaf_schema, layout, store).await
+}
+
+/// Outcome of the parsing of a recipient list, which comes with its annotated report when it is invalid and one was
+/// requested
+enum ParsedRecipients {
+    Campaign(CampaignCsvParsed),
+    Report(CampaignCsvParsed, ValidationReport),
+    /// Only the CSV files can be annotated, so the reports requested for the other formats are rejected unread
+    UnsupportedReport,
+}
+
+/// Parse the recipient list, along with the annotated report of the invalid files when one is requested.
+fn parse(reader: impl Read, options: &ParserOptions, report: ErrorReport) -> Result<ParsedRecipients, ParserError> {
+    match report {
+        ErrorReport::Annotated if options.format != InputFormat::Csv => Ok(ParsedRecipients::UnsupportedReport),
+        ErrorReport::Annotated => match parse_with_report(reader, options)? {
+            (parsed_csv, Some(report)) => Ok(ParsedRecipients::Report(parsed_csv, report)),
+            (parsed_csv, None) => Ok(ParsedRecipients::Campaign(parsed_csv)),
+        },
+        ErrorReport::List => Ok(ParsedRecipients::Campaign(CampaignCsvParsed::parse(reader, options)?)),
+    }
+}
+
+/// Send the annotated report of an invalid file as a CSV download, with its summary in the `X-Message` header, or go
+/// on with the upload otherwise.
+async fn upload_or_report(
+    parsed_csv: Result<ParsedRecipients, ParserError>,
+    leaf_schema: &LeafSchema,
+    layout: CampaignLayout,
+    store: &dyn CampaignStore,
+) -> response::R {
+    let parsed_csv = match parsed_csv {
+        Ok(ParsedRecipients::Report(parsed_csv, report)) => {
+            let response_json = json!(ValidationReportResponse {
+                status: String::from("Invalid csv file."),
+                summary: report.summary,
+                dialect: parsed_csv.dialect,
+            });
+            let attachment = Attachment {
+                file_name: String::from(REPORT_FILE_NAME),
+                content_type: String::from("text/csv; charset=utf-8"),
+                content: report.csv,
+            };
+
+            return response::with_attachment(response::bad_request(response_json), attachment);
+        }
+        Ok(ParsedRecipients::UnsupportedReport) => {
+            let response_json = json!(GeneralErrorResponse {
+                message: String::from("The annotated report is only available for CSV files"),
+            });
 
-/// Create request common handler. It validates the received data, creates the merkle tree and uploads it to ipfs.
-async fn handler(decimals: usize, buffer: &[u8]) -> response::R {
-    let rdr = ReaderBuilder::new().from_reader(buffer);
-    let parsed_csv = CampaignCsvParsed::build(rdr, decimals);
+            return response::bad_request(response_json);
+        }
+        Ok(ParsedRecipients::Campaign(parsed_csv)) => Ok(parsed_csv),
+        Err(error) => Err(error),
+    };
+
+    upload(parsed_csv, leaf_schema, layout, store).await
+}
 
+/// Check the outcome of the parsing process, create the merkle tree following the leaf schema and upload the campaign
+/// to the campaign store.
+async fn upload(
+    parsed_csv: Result<CampaignCsvParsed, ParserError>,
+    leaf_schema: &LeafSchema,
+    layout: CampaignLayout,
+    store: &dyn CampaignStore,
+) -> response::R {
     if let Err(error) = parsed_csv {
         let response_json = json!(GeneralErrorResponse {
             message: format!("There was a problem in csv file parsing process: {}", error),
@@ -36,90 +148,279 @@ async fn handler(decimals: usize, buffer: &[u8]) -> response::R {
         let response_json = json!(ValidationErrorResponse {
             status: String::from("Invalid csv file."),
             errors: parsed_csv.validation_errors,
+            dialect: parsed_csv.dialect,
+            warnings: parsed_csv.warnings,
+        });
+
+        return response::bad_request(response_json);
+    }
+
+    let tree = build_tree(&parsed_csv, leaf_schema);
+    if let Err(error) = tree {
+        let response_json = json!(ValidationErrorResponse {
+            status: String::from("Invalid csv file."),
+            errors: vec![error],
+            dialect: parsed_csv.dialect,
+            warnings: parsed_csv.warnings,
         });
 
         return response::bad_request(response_json);
     }
+    let tree = tree.unwrap();
+    let root = tree.root();
+
+    let recipients: Vec<RecipientDto> = parsed_csv
+        .records
+        .iter()
+        .map(|x| RecipientDto { address: x.address.clone(), amount: x.amount.to_string() })
+        .collect();
+
+    let cid = match layout {
+        CampaignLayout::Single => {
+            let campaign = PersistentCampaignDto {
+                version: CAMPAIGN_SCHEMA_VERSION,
+                total_amount: parsed_csv.total_amount.to_string(),
+                number_of_recipients: parsed_csv.number_of_recipients,
+                root: root.clone(),
+                merkle_tree: tree,
+                leaf_schema: leaf_schema.clone(),
+                address_index: Some(build_address_index(&recipients)),
+                recipients,
+            };
+            put_json(store, &campaign).await
+        }
+        CampaignLayout::Sharded => upload_shards(&parsed_csv, &recipients, &tree, leaf_schema, store).await,
+    };
+
+    if cid.is_err() {
+        let response_json =
+            json!(GeneralErrorResponse { message: String::from("There was an error uploading the campaign to ipfs") });
+
+        return response::internal_server_error(response_json);
+    }
+
+    let response_json = json!(UploadSuccessResponse {
+        status: "Upload successful".to_string(),
+        total: parsed_csv.total_amount.to_string(),
+        recipients: parsed_csv.number_of_recipients.to_string(),
+        root,
+        cid: cid.unwrap(),
+        dialect: parsed_csv.dialect,
+        merged: parsed_csv.merged,
+        warnings: parsed_csv.warnings,
+    });
+
+    response::ok(response_json)
+}
 
+/// Build the merkle tree of the parsed campaign following the leaf schema. A value that does not fit its leaf type is
+/// reported as a validation error of its row.
+pub(crate) fn build_tree(
+    parsed_csv: &CampaignCsvParsed,
+    leaf_schema: &LeafSchema,
+) -> Result<MerkleTreeDump, ValidationError> {
     let leaves = parsed_csv
         .records
         .iter()
         .enumerate()
-        .map(|(i, r)| vec![i.to_string(), r.address.clone(), r.amount.to_string()])
+        .map(|(i, r)| leaf_schema.leaf_values(i, &r.address, &r.amount.to_string(), &r.extra))
         .collect();
 
-    let tree = StandardMerkleTree::of(leaves, &["uint".to_string(), "address".to_string(), "uint256".to_string()]);
+    MerkleTreeDump::build(leaves, leaf_schema.types()).map_err(|(index, message)| ValidationError {
+        row: index + 2,
+        message,
+        ..Default::default()
+    })
+}
 
-    let tree_json = serde_json::to_string(&tree.dump()).unwrap();
+/// Upload every shard of the campaign and then the manifest pointing to them. Returns the CID of the manifest.
+async fn upload_shards(
+    parsed_csv: &CampaignCsvParsed,
+    recipients: &[RecipientDto],
+    tree: &MerkleTreeDump,
+    leaf_schema: &LeafSchema,
+    store: &dyn CampaignStore,
+) -> StoreResult<String> {
+    let prefix_length = prefix_length_for(recipients.len());
+    let mut shards = BTreeMap::new();
+    for (key, shard) in build_shards(recipients, tree, prefix_length)? {
+        shards.insert(key, put_json(store, &shard).await?);
+    }
 
-    let ipfs_response = upload_to_ipfs(PersistentCampaignDto {
+    let manifest = ShardedCampaignDto {
+        version: CAMPAIGN_SCHEMA_VERSION,
+        layout: SHARDED_LAYOUT.to_string(),
         total_amount: parsed_csv.total_amount.to_string(),
         number_of_recipients: parsed_csv.number_of_recipients,
-        merkle_tree: tree_json,
         root: tree.root(),
-        recipients: parsed_csv
-            .records
-            .iter()
-            .map(|x| RecipientDto { address: x.address.clone(), amount: x.amount.to_string() })
-            .collect(),
-    })
-    .await;
-    if ipfs_response.is_err() {
-        let response_json =
-            json!(GeneralErrorResponse { message: String::from("There was an error uploading the campaign to ipfs") });
+        prefix_length,
+        leaf_schema: leaf_schema.clone(),
+        shards,
+    };
+    put_json(store, &manifest).await
+}
 
-        return response::internal_server_error(response_json);
+/// Parse the decimals query parameter. When it is omitted or set to `raw`, the amounts are expected in base units.
+fn parse_decimals(decimals: &str) -> Result<Option<usize>, String> {
+    match decimals.trim() {
+        "" | "raw" => Ok(None),
+        decimals => decimals.parse::<u16>().map(|d| Some(d.into())).map_err(|_| {
+            String::from("Decimals query parameter should be a valid integer, or `raw` for amounts in base units")
+        }),
     }
+}
+
+/// Build the parser options out of the query parameters of a create request. The amounts of the rules profile are
+/// checked against the decimals up front, so that a profile that does not fit the campaign is reported before the file
+/// is read.
+pub(crate) fn parser_options(params: &Create) -> Result<ParserOptions, String> {
+    let decimals = parse_decimals(&params.decimals)?;
+    let rules = CampaignRules::from_env(&params.rules)?;
+    rules.amount_limits(decimals.unwrap_or_default())?;
+
+    Ok(ParserOptions {
+        decimals,
+        leaf_schema: LeafSchema::parse(&params.leaf_encoding)?,
+        sheet: Some(params.sheet.trim().to_string()).filter(|sheet| !sheet.is_empty()),
+        aliases: ColumnAliases::parse(&params.aliases)?,
+        duplicates: Duplicates::parse(&params.duplicates)?,
+        rules,
+        checksum: AddressChecksum::parse(&params.checksum)?,
+        ..Default::default()
+    })
+}
 
-    let ipfs_response = ipfs_response.unwrap();
-    let deserialized_response = try_deserialize_pinata_response(&ipfs_response);
+/// Read the query parameters of a Vercel request the way Warp reads the ones of the create endpoints.
+pub(crate) fn vercel_params(req: &Vercel::Request) -> Create {
+    let url = Url::parse(&req.uri().to_string()).unwrap();
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    serde_json::from_value(json!(query)).unwrap_or_default()
+}
 
-    if deserialized_response.is_err() {
-        let response_json =
-            json!(GeneralErrorResponse { message: String::from("There was an error uploading the campaign to ipfs") });
+/// Get the parser options matching a file uploaded through a form, whose format is found from its name or content
+/// type. The files of unknown formats are read as CSV files.
+pub(crate) fn options_for_upload(
+    options: &ParserOptions,
+    file_name: Option<&str>,
+    content_type: Option<&str>,
+) -> ParserOptions {
+    let format = InputFormat::from_upload(file_name, content_type).unwrap_or_default();
+    ParserOptions { format, ..options.clone() }
+}
 
-        return response::internal_server_error(response_json);
+/// Run a parsing function over the recipient list of a Warp request. Returns `None` when a multipart form does not
+/// contain the `data` file.
+///
+/// The list is streamed into the parser instead of being buffered. The parser is synchronous, so it runs on a blocking
+/// thread that pulls the chunks from the request as it needs them.
+pub(crate) async fn read_recipients<T, F>(
+    recipients: Recipients,
+    options: &ParserOptions,
+    parse: F,
+) -> Option<Result<T, ParserError>>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut dyn Read, &ParserOptions) -> Result<T, ParserError> + Send + 'static,
+{
+    match recipients {
+        Recipients::Form(mut form) => {
+            while let Some(Ok(part)) = form.next().await {
+                if part.name() == "data" {
+                    let options = options_for_upload(options, part.filename(), part.content_type());
+                    return Some(parse_stream(into_bytes(part.stream()), options, parse).await);
+                }
+            }
+            None
+        }
+        Recipients::Body(format, stream) => {
+            Some(parse_stream(stream, ParserOptions { format, ..options.clone() }, parse).await)
+        }
     }
+}
 
-    let deserialized_response = deserialized_response.unwrap();
+fn into_bytes<B: Buf>(
+    stream: impl Stream<Item = Result<B, warp::Error>> + Send + 'static,
+) -> impl Stream<Item = Result<Bytes, warp::Error>> + Send + 'static {
+    stream.map_ok(|mut chunk| {
+        let length = chunk.remaining();
+        chunk.copy_to_bytes(length)
+    })
+}
 
-    let response_json = json!(UploadSuccessResponse {
-        status: "Upload successful".to_string(),
-        total: parsed_csv.total_amount.to_string(),
-        recipients: parsed_csv.number_of_recipients.to_string(),
-        root: tree.root(),
-        cid: deserialized_response.ipfs_hash,
-    });
+async fn parse_stream<S, T, F>(stream: S, options: ParserOptions, parse: F) -> Result<T, ParserError>
+where
+    S: Stream<Item = Result<Bytes, warp::Error>> + Send + 'static,
+    T: Send + 'static,
+    F: FnOnce(&mut dyn Read, &ParserOptions) -> Result<T, ParserError> + Send + 'static,
+{
+    let mut reader = SyncIoBridge::new(StreamReader::new(Box::pin(stream.map_err(io::Error::other))));
+    tokio::task::spawn_blocking(move || parse(&mut reader, &options)).await.unwrap_or_else(|error| Err(error.into()))
+}
 
-    response::ok(response_json)
+/// Extract the recipient list of a Warp request. JSON and NDJSON lists are accepted as the body of the request, with
+/// the matching content type, and CSV files as the `data` part of a multipart form.
+pub(crate) fn with_recipients() -> impl Filter<Extract = (Recipients,), Error = warp::Rejection> + Clone {
+    let body = warp::header::<String>("content-type")
+        .and_then(|content_type: String| async move {
+            match InputFormat::from_content_type(&content_type) {
+                Some(format) if format != InputFormat::Csv => Ok(format),
+                _ => Err(warp::reject()),
+            }
+        })
+        .and(warp::body::content_length_limit(MAX_BODY_LENGTH))
+        .and(warp::body::stream())
+        .map(|format, stream| Recipients::Body(format, Box::pin(into_bytes(stream))));
+    let form = warp::multipart::form().max_length(MAX_BODY_LENGTH).map(Recipients::Form);
+
+    body.or(form).unify()
+}
+
+/// Get the format of the recipient list sent in the body of a Vercel request. Multipart forms yield `None`.
+pub(crate) fn vercel_body_format(req: &Vercel::Request) -> Option<InputFormat> {
+    let content_type = req.headers().get("content-type").and_then(|v| v.to_str().ok())?;
+    InputFormat::from_content_type(content_type).filter(|format| *format != InputFormat::Csv)
+}
+
+/// Open the multipart body of a Vercel request, whose first entry is expected to be the CSV file.
+pub(crate) fn vercel_multipart(req: &Vercel::Request) -> Result<Multipart<&[u8]>, String> {
+    let boundary = req
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("multipart/form-data; boundary="));
+
+    match boundary {
+        Some(boundary) => Ok(Multipart::with_body(req.body().as_ref(), boundary)),
+        None => Err(String::from("Invalid content type header")),
+    }
 }
 
 /// Warp specific handler for the create endpoint
-pub async fn handler_to_warp(params: Create, form: FormData) -> WebResult<impl warp::Reply> {
-    let decimals: Result<u16, ParseIntError> = params.decimals.parse();
-    if decimals.is_err() {
-        let response_json = json!(GeneralErrorResponse {
-            message: String::from("Decimals query parameter is mandatory and should be a valid integer in order to create a valid campaign!"),
-        });
+pub async fn handler_to_warp(
+    params: Create,
+    recipients: Recipients,
+    store: Arc<dyn CampaignStore>,
+) -> WebResult<impl warp::Reply> {
+    let options = parser_options(&params).and_then(|options| Ok((options, ErrorReport::parse(&params.report)?)));
+    if let Err(message) = options {
+        let response_json = json!(GeneralErrorResponse { message });
 
         return Ok(response::to_warp(response::bad_request(response_json)));
     }
-    let decimals = decimals.unwrap_or_default();
-    let mut form = form;
-    while let Some(Ok(part)) = form.next().await {
-        let name = part.name();
+    let (options, report) = options.unwrap();
 
-        if name == "data" {
-            let mut stream = part.stream();
-            let mut buffer = Vec::new();
+    let layout = CampaignLayout::parse(&params.layout);
+    if let Err(message) = layout {
+        let response_json = json!(GeneralErrorResponse { message });
 
-            while let Ok(Some(chunk)) = stream.try_next().await {
-                chunk.reader().read_to_end(&mut buffer).unwrap();
-            }
+        return Ok(response::to_warp(response::bad_request(response_json)));
+    }
+    let layout = layout.unwrap();
 
-            let result = handler(decimals.into(), &buffer).await;
-            return Ok(response::to_warp(result));
-        }
+    let parsed_csv = read_recipients(recipients, &options, move |reader, options| parse(reader, options, report)).await;
+    if let Some(parsed_csv) = parsed_csv {
+        let result = upload_or_report(parsed_csv, &options.leaf_schema, layout, store.as_ref()).await;
+        return Ok(response::to_warp(result));
     }
 
     let response_json = json!(GeneralErrorResponse {
@@ -131,41 +432,54 @@ pub async fn handler_to_warp(params: Create, form: FormData) -> WebResult<impl w
 /// Vercel specific handler for the create endpoint
 pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<Vercel::Body>, Vercel::Error> {
     // ------------------------------------------------------------
-    // Extract query parameters from the URL: decimals
+    // Extract query parameters from the URL: decimals, layout, leaf_encoding, sheet, aliases, duplicates, rules,
+    // checksum, report
     // ------------------------------------------------------------
 
-    let url = Url::parse(&req.uri().to_string()).unwrap();
-    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
-    let decimals = query.get("decimals");
+    let params = vercel_params(&req);
+    let options = parser_options(&params).and_then(|options| Ok((options, ErrorReport::parse(&params.report)?)));
+    if let Err(message) = options {
+        let response_json = json!(GeneralErrorResponse { message });
 
-    if decimals.is_none() {
-        let response_json = json!(GeneralErrorResponse {
-            message: String::from("Decimals query parameter is mandatory in order to create a valid campaign!"),
-        });
+        return response::to_vercel(response::bad_request(response_json));
+    }
 
-        return response::to_vercel(response::ok(response_json));
+    let layout = CampaignLayout::parse(&params.layout);
+    if let Err(message) = layout {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::bad_request(response_json));
     }
 
+    let ((options, report), layout) = (options.unwrap(), layout.unwrap());
+
+    let store = store::from_env();
+    if let Err(message) = store {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::internal_server_error(response_json));
+    }
+
+    let store = store.unwrap();
+
     // ------------------------------------------------------------
-    // Extract form data from the body: file
+    // Extract the recipient list from the body: JSON, NDJSON or a form with the CSV file
     // ------------------------------------------------------------
 
-    let boundary = req
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.strip_prefix("multipart/form-data; boundary="));
+    if let Some(format) = vercel_body_format(&req) {
+        let options = ParserOptions { format, ..options };
+        let result = handler(options, layout, report, req.body().as_ref(), store.as_ref()).await;
+        return response::to_vercel(result);
+    }
 
-    if boundary.is_none() {
-        let response_json = json!(GeneralErrorResponse { message: String::from("Invalid content type header") });
+    let data = vercel_multipart(&req);
+    if let Err(message) = data {
+        let response_json = json!(GeneralErrorResponse { message });
 
         return response::to_vercel(response::ok(response_json));
     }
 
-    let boundary = boundary.unwrap();
-    let body = req.body().to_vec();
-
-    let mut data = multipart::server::Multipart::with_body(body.as_slice(), boundary);
+    let mut data = data.unwrap();
     let file = data.read_entry();
     if let Err(error) = file {
         let response_json = json!(GeneralErrorResponse { message: error.to_string() });
@@ -181,48 +495,66 @@ pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<
         return response::to_vercel(response::ok(response_json));
     }
 
-    let mut file = file.unwrap();
-    let mut buffer: Vec<u8> = vec![];
-
-    if let Err(error) = file.data.read_to_end(&mut buffer) {
-        let response_json = json!(GeneralErrorResponse { message: format!("Could not read body data {}", error) });
-
-        return response::to_vercel(response::ok(response_json));
-    }
-
-    // ------------------------------------------------------------
-    // Format arguments for the generic handler
-    // ------------------------------------------------------------
-
-    let decimals: Result<u16, ParseIntError> = decimals.unwrap().parse();
-    if decimals.is_err() {
-        let response_json = json!(GeneralErrorResponse {
-            message: String::from("Decimals query parameter is mandatory and should be a valid integer in order to create a valid campaign!"),
-        });
-
-        return response::to_vercel(response::ok(response_json));
-    }
-    let decimals = decimals.unwrap_or_default();
+    let file = file.unwrap();
+    let content_type = file.headers.content_type.as_ref().map(|mime| mime.to_string());
+    let options = options_for_upload(&options, file.headers.filename.as_deref(), content_type.as_deref());
 
-    let result = handler(decimals.into(), &buffer).await;
+    let result = handler(options, layout, report, file.data, store.as_ref()).await;
     response::to_vercel(result)
 }
 
 /// Bind the route with the handler for the Warp handler.
-pub fn build_route() -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+pub fn build_route(
+    store: Arc<dyn CampaignStore>,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::path!("api" / "create")
         .and(warp::post())
         .and(warp::query::query::<Create>())
-        .and(warp::multipart::form().max_length(100_000_000))
+        .and(with_recipients())
+        .and(super::with_store(store))
         .and_then(handler_to_warp)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::async_test::{setup_env_vars, SERVER};
+    use crate::{
+        controller::{eligibility, verify},
+        data_objects::{dto::VerifyDto, query_param::Eligibility},
+        services::{
+            cache::{CampaignCache, DEFAULT_CACHE_MAX_BYTES},
+            store::{get_json, memory::MemoryStore, pinata::PinataStore},
+        },
+        utils::async_test::{setup_env_vars, SERVER},
+    };
     use warp::http::StatusCode;
 
+    fn options(decimals: usize) -> ParserOptions {
+        ParserOptions { decimals: Some(decimals), ..Default::default() }
+    }
+
+    #[test]
+    fn test_parse_decimals() {
+        assert_eq!(parse_decimals(""), Ok(None));
+        assert_eq!(parse_decimals("raw"), Ok(None));
+        assert_eq!(parse_decimals("18"), Ok(Some(18)));
+        assert!(parse_decimals("-1").is_err());
+    }
+
+    #[test]
+    fn test_parser_options_rules_and_checksum() {
+        let params = |rules: &str, checksum: &str| Create {
+            decimals: String::from("2"),
+            rules: rules.to_string(),
+            checksum: checksum.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(parser_options(&params("", "")).unwrap().rules, CampaignRules::default());
+        assert_eq!(parser_options(&params("unknown", "")).unwrap_err(), "Unknown campaign rules profile unknown");
+        assert_eq!(parser_options(&params("", "strict")).unwrap().checksum, AddressChecksum::Strict);
+        assert!(parser_options(&params("", "eip55")).is_err());
+    }
+
     #[tokio::test]
     async fn test_valid_csv_upload() {
         let mut server = SERVER.lock().await;
@@ -234,19 +566,293 @@ mod tests {
             .create();
 
         let csv_data = b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::OK.as_u16());
         mock.assert();
         drop(server);
     }
 
+    #[tokio::test]
+    async fn test_valid_csv_upload_to_memory_store() {
+        let store = MemoryStore::default();
+        let csv_data = b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
+        let response = handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &store).await;
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        let cid = response.message["cid"].as_str().unwrap();
+        let campaign: PersistentCampaignDto = get_json(&store, cid).await.unwrap();
+        assert_eq!(campaign.version, CAMPAIGN_SCHEMA_VERSION);
+        assert_eq!(campaign.merkle_tree.values.len(), 2);
+        assert_eq!(campaign.number_of_recipients, 2);
+        assert_eq!(campaign.total_amount, "30000");
+        assert_eq!(campaign.address_index.unwrap()["0xf976af93b0a5a9f55a7f285a3b5355b8575eb5bc"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_sharded_csv_upload_to_memory_store() {
+        let store = MemoryStore::default();
+        let csv_data = b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
+        let response = handler(options(2), CampaignLayout::Sharded, ErrorReport::List, &csv_data[..], &store).await;
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        let cid = response.message["cid"].as_str().unwrap().to_string();
+        let manifest: ShardedCampaignDto = get_json(&store, &cid).await.unwrap();
+        assert_eq!(manifest.layout, SHARDED_LAYOUT);
+        assert_eq!(manifest.total_amount, "30000");
+        assert_eq!(manifest.shards.len(), 1);
+
+        let cache = CampaignCache::new(DEFAULT_CACHE_MAX_BYTES);
+        let address = "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc".to_string();
+        let eligibility = Eligibility { address, cid: cid.clone() };
+        let response = eligibility::handler(eligibility, &store, &cache).await;
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        assert_eq!(response.message["index"], 1);
+        assert_eq!(response.message["amount"], "20000");
+        assert_eq!(response.message["proof"].as_array().unwrap().len(), 1);
+
+        let address = "0x0000000000000000000000000000000000000001".to_string();
+        let response = eligibility::handler(Eligibility { address, cid }, &store, &cache).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
+    }
+
+    #[tokio::test]
+    async fn test_csv_upload_with_custom_leaf_schema() {
+        let store = MemoryStore::default();
+        let csv_data = b"address,amount,start_time\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,1700000000\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200,1700000100";
+        let leaf_schema = LeafSchema::parse("uint256,address,uint128,start_time:uint40").unwrap();
+        let options = ParserOptions { decimals: None, leaf_schema: leaf_schema.clone(), ..Default::default() };
+        let response = handler(options, CampaignLayout::Single, ErrorReport::List, &csv_data[..], &store).await;
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        let cid = response.message["cid"].as_str().unwrap().to_string();
+        let campaign: PersistentCampaignDto = get_json(&store, &cid).await.unwrap();
+        assert_eq!(campaign.leaf_schema, leaf_schema);
+        assert_eq!(campaign.merkle_tree.leaf_encoding, vec!["uint256", "address", "uint128", "uint40"]);
+
+        let cache = CampaignCache::new(DEFAULT_CACHE_MAX_BYTES);
+        let address = "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc".to_string();
+        let response =
+            eligibility::handler(Eligibility { address: address.clone(), cid: cid.clone() }, &store, &cache).await;
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        assert_eq!(response.message["leaf"], json!(["1", address, "200", "1700000100"]));
+
+        let request = VerifyDto {
+            root: None,
+            cid: Some(cid),
+            index: 1,
+            address,
+            amount: "200".to_string(),
+            proof: serde_json::from_value(response.message["proof"].clone()).unwrap(),
+            leaf_encoding: String::new(),
+            fields: HashMap::from([("start_time".to_string(), "1700000100".to_string())]),
+        };
+        let response = verify::handler(request, &store, &cache).await;
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        assert_eq!(response.message["valid"], true);
+    }
+
+    #[tokio::test]
+    async fn test_csv_upload_with_invalid_leaf_values() {
+        let store = MemoryStore::default();
+        let leaf_schema = LeafSchema::parse("uint256,address,uint128,start_time:uint40").unwrap();
+
+        let csv_data = b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200";
+        let options = ParserOptions { decimals: None, leaf_schema: leaf_schema.clone(), ..Default::default() };
+        let response = handler(options, CampaignLayout::Single, ErrorReport::List, &csv_data[..], &store).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
+        assert_eq!(response.message["errors"][0]["row"], 1);
+
+        let csv_data = b"address,amount,start_time\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,1700000000\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200,-1";
+        let options = ParserOptions { decimals: None, leaf_schema, ..Default::default() };
+        let response = handler(options, CampaignLayout::Single, ErrorReport::List, &csv_data[..], &store).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
+        assert_eq!(response.message["errors"][0]["row"], 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_streamed_csv_upload_through_route() {
+        let store = Arc::new(MemoryStore::default());
+        let rows: String = (1..=1000).map(|i| format!("0x{:040x},{}\n", i, i)).collect();
+        let body = format!(
+            "--boundary\r\nContent-Disposition: form-data; name=\"data\"; filename=\"data.csv\"\r\n\r\naddress,amount\n{}\r\n--boundary--\r\n",
+            rows
+        );
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create?decimals=0")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&build_route(store.clone()))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let campaign: PersistentCampaignDto = get_json(store.as_ref(), message["cid"].as_str().unwrap()).await.unwrap();
+        assert_eq!(campaign.number_of_recipients, 1000);
+        assert_eq!(campaign.total_amount, "500500");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spreadsheet_upload_through_route() {
+        let store = Arc::new(MemoryStore::default());
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet().set_name("Airdrop").unwrap();
+        sheet.write_row(0, 0, ["address", "amount"]).unwrap();
+        sheet.write_string(1, 0, "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491").unwrap();
+        sheet.write_number(1, 1, 100).unwrap();
+        sheet.write_string(2, 0, "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc").unwrap();
+        sheet.write_string(2, 1, "200.5").unwrap();
+
+        let mut body =
+            b"--boundary\r\nContent-Disposition: form-data; name=\"data\"; filename=\"data.xlsx\"\r\n\r\n".to_vec();
+        body.extend(workbook.save_to_buffer().unwrap());
+        body.extend(b"\r\n--boundary--\r\n");
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create?decimals=1&sheet=Airdrop")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&build_route(store.clone()))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let campaign: PersistentCampaignDto = get_json(store.as_ref(), message["cid"].as_str().unwrap()).await.unwrap();
+        assert_eq!(campaign.total_amount, "3005");
+    }
+
+    #[tokio::test]
+    async fn test_csv_upload_reports_the_merged_rows() {
+        let store = MemoryStore::default();
+        let csv_data = b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,50.0";
+        let params = Create { decimals: String::from("2"), duplicates: String::from("merge"), ..Default::default() };
+        let options = parser_options(&params).unwrap();
+        let response = handler(options, CampaignLayout::Single, ErrorReport::List, &csv_data[..], &store).await;
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        assert_eq!(response.message["recipients"], "2");
+        assert_eq!(response.message["merged"][0]["rows"], json!([2, 4]));
+        let campaign: PersistentCampaignDto =
+            get_json(&store, response.message["cid"].as_str().unwrap()).await.unwrap();
+        assert_eq!(campaign.recipients[0].amount, "15000");
+    }
+
+    #[tokio::test]
+    async fn test_csv_upload_reports_the_dialect() {
+        let store = MemoryStore::default();
+        let csv_data = b"\xEF\xBB\xBFaddress;amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491;100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc;200.0";
+        let response = handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &store).await;
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        assert_eq!(response.message["total"], "30000");
+        assert_eq!(response.message["dialect"], json!({ "encoding": "UTF-8", "bom": true, "delimiter": ";" }));
+
+        let csv_data = b"address\tamount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\t100,5\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc\t200";
+        let response = handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &store).await;
+
+        assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
+        assert_eq!(response.message["errors"][0]["column"], "B");
+        assert_eq!(response.message["dialect"]["delimiter"], "\t");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_csv_upload_returns_the_annotated_report() {
+        let store = Arc::new(MemoryStore::default());
+        let body = "--boundary\r\nContent-Disposition: form-data; name=\"data\"; filename=\"data.csv\"\r\n\r\naddress,amount\n0xThisIsNotAnAddress,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,0\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,200.0\r\n--boundary--\r\n";
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create?decimals=2&report=annotated")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&build_route(store.clone()))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()["content-type"], "text/csv; charset=utf-8");
+        assert_eq!(response.headers()["content-disposition"], "attachment; filename=\"recipients-report.csv\"");
+        let message: serde_json::Value =
+            serde_json::from_slice(response.headers()[response::MESSAGE_HEADER].as_bytes()).unwrap();
+        let mut lines = str::from_utf8(response.body()).unwrap().lines();
+        assert_eq!(lines.next(), Some("address,amount,error,warning"));
+        assert_eq!(lines.next(), Some("0xThisIsNotAnAddress,100.0,Invalid Ethereum address,"));
+        assert_eq!(lines.next(), Some("0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,0,The amount cannot be 0,"));
+        assert_eq!(message["summary"]["rows"], 3);
+        assert_eq!(message["summary"]["invalid_rows"], 2);
+        assert_eq!(message["summary"]["errors"][0]["count"], 1);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create?report=pdf")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&build_route(store.clone()))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create?report=annotated")
+            .header("content-type", "application/json")
+            .body(r#"[{"address": "0xThisIsNotAnAddress", "amount": "1"}]"#)
+            .reply(&build_route(store))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(message["message"], "The annotated report is only available for CSV files");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_json_upload_through_route() {
+        let store = Arc::new(MemoryStore::default());
+        let body = r#"[
+            {"address": "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", "amount": "100"},
+            {"address": "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc", "amount": 200}
+        ]"#;
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create?decimals=0")
+            .header("content-type", "application/json")
+            .body(body)
+            .reply(&build_route(store.clone()))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let campaign: PersistentCampaignDto = get_json(store.as_ref(), message["cid"].as_str().unwrap()).await.unwrap();
+        assert_eq!(campaign.total_amount, "300");
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create?decimals=0")
+            .header("content-type", "application/x-ndjson")
+            .body("{\"address\": \"0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\", \"amount\": \"0\"}\n")
+            .reply(&build_route(store.clone()))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(message["errors"][0]["index"], 0);
+        assert_eq!(message["errors"][0]["row"], 1);
+    }
+
     #[tokio::test]
     async fn test_csv_with_wrong_header() {
         let server = SERVER.lock().await;
         setup_env_vars(&server);
         let csv_data =b"address,amount_invalid\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -259,7 +865,9 @@ mod tests {
 
         let csv_data =
             b"address\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -270,7 +878,9 @@ mod tests {
         let server = SERVER.lock().await;
         setup_env_vars(&server);
         let csv_data =b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -281,7 +891,9 @@ mod tests {
         let server = SERVER.lock().await;
         setup_env_vars(&server);
         let csv_data = b"address,amount\n0xThisIsNotAnAddress,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -292,7 +904,9 @@ mod tests {
         let server = SERVER.lock().await;
         setup_env_vars(&server);
         let csv_data =b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -304,7 +918,9 @@ mod tests {
         setup_env_vars(&server);
 
         let csv_data = b"address,amount\n0x0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,alphanumeric_amount\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -315,7 +931,9 @@ mod tests {
         let server = SERVER.lock().await;
         setup_env_vars(&server);
         let csv_data = b"address,amount\n0x0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -326,7 +944,9 @@ mod tests {
         let server = SERVER.lock().await;
         setup_env_vars(&server);
         let csv_data = b"address,amount\n0x0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,-1\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -337,7 +957,9 @@ mod tests {
         let server = SERVER.lock().await;
         setup_env_vars(&server);
         let csv_data = b"address,amount\n0x0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1.1234\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
-        let response = handler(2, csv_data).await;
+        let response =
+            handler(options(2), CampaignLayout::Single, ErrorReport::List, &csv_data[..], &PinataStore::from_env())
+                .await;
 
         assert_eq!(response.status, StatusCode::BAD_REQUEST.as_u16());
         drop(server);
@@ -0,0 +1,267 @@
+use crate::{
+    controller::create::{
+        build_tree, options_for_upload, parser_options, read_recipients, vercel_body_format, vercel_multipart,
+        vercel_params, with_recipients, ParserError, Recipients,
+    },
+    csv_autofix::{autofix_and_parse, AutofixedCsv, Rounding},
+    csv_campaign_parser::{CampaignCsvParsed, InputFormat, ParserOptions},
+    data_objects::{
+        query_param::Create,
+        response::{self, GeneralErrorResponse, PreviewResponse},
+    },
+    utils::leaf_schema::LeafSchema,
+    WebResult,
+};
+
+use std::io::Read;
+
+use serde_json::json;
+use vercel_runtime as Vercel;
+use warp::Filter;
+
+/// Create preview request common handler. It validates the received data and creates the merkle tree the same way the
+/// create endpoint does, but reports the outcome instead of uploading the campaign.
+fn handler<R: Read>(options: ParserOptions, fix: Option<Rounding>, reader: R) -> response::R {
+    preview(parse(reader, &options, fix), &options.leaf_schema)
+}
+
+/// Parse the recipient list, cleaning it up with the auto-fix pass first when one is requested. Only the CSV files can
+/// be fixed, so the other formats are parsed as they are.
+fn parse(
+    reader: impl Read,
+    options: &ParserOptions,
+    fix: Option<Rounding>,
+) -> Result<(CampaignCsvParsed, Option<AutofixedCsv>), ParserError> {
+    match fix {
+        Some(rounding) if options.format == InputFormat::Csv => autofix_and_parse(reader, options, rounding),
+        _ => Ok((CampaignCsvParsed::parse(reader, options)?, None)),
+    }
+}
+
+/// Check the outcome of the parsing process and create the merkle tree, reporting every validation error found. When
+/// the file went through the auto-fix pass, the outcome is the one of the cleaned file, which is returned along with
+/// its changelog.
+fn preview(
+    parsed_csv: Result<(CampaignCsvParsed, Option<AutofixedCsv>), ParserError>,
+    leaf_schema: &LeafSchema,
+) -> response::R {
+    if let Err(error) = parsed_csv {
+        let response_json = json!(GeneralErrorResponse {
+            message: format!("There was a problem in csv file parsing process: {}", error),
+        });
+
+        return response::internal_server_error(response_json);
+    }
+
+    let (mut parsed_csv, fixed) = parsed_csv.unwrap();
+    let mut response = PreviewResponse {
+        status: String::from("Invalid csv file."),
+        valid: false,
+        root: None,
+        total: None,
+        recipients: None,
+        depth: None,
+        errors: Vec::new(),
+        dialect: parsed_csv.dialect.clone(),
+        merged: Vec::new(),
+        warnings: std::mem::take(&mut parsed_csv.warnings),
+        fixed,
+    };
+
+    if !parsed_csv.validation_errors.is_empty() {
+        response.errors = parsed_csv.validation_errors;
+        return response::ok(json!(response));
+    }
+
+    match build_tree(&parsed_csv, leaf_schema) {
+        Ok(tree) => {
+            response.status = String::from("Valid csv file.");
+            response.valid = true;
+            response.root = Some(tree.root());
+            response.total = Some(parsed_csv.total_amount.to_string());
+            response.recipients = Some(parsed_csv.number_of_recipients.to_string());
+            response.depth = Some(tree.depth());
+            response.merged = parsed_csv.merged;
+        }
+        Err(error) => response.errors.push(error),
+    }
+
+    response::ok(json!(response))
+}
+
+/// Warp specific handler for the create preview endpoint
+pub async fn handler_to_warp(params: Create, recipients: Recipients) -> WebResult<impl warp::Reply> {
+    let options = parser_options(&params).and_then(|options| Ok((options, Rounding::parse(&params.fix)?)));
+    if let Err(message) = options {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return Ok(response::to_warp(response::bad_request(response_json)));
+    }
+    let (options, fix) = options.unwrap();
+    let options = ParserOptions { all_errors: true, ..options };
+
+    if let Some(parsed_csv) =
+        read_recipients(recipients, &options, move |reader, options| parse(reader, options, fix)).await
+    {
+        return Ok(response::to_warp(preview(parsed_csv, &options.leaf_schema)));
+    }
+
+    let response_json = json!(GeneralErrorResponse {
+        message: "The request form data did not contain recipients csv file".to_string()
+    });
+    Ok(response::to_warp(response::bad_request(response_json)))
+}
+
+/// Vercel specific handler for the create preview endpoint
+pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<Vercel::Body>, Vercel::Error> {
+    // ------------------------------------------------------------
+    // Extract query parameters from the URL: decimals, leaf_encoding, sheet, aliases, duplicates, rules, checksum, fix
+    // ------------------------------------------------------------
+
+    let params = vercel_params(&req);
+    let options = parser_options(&params).and_then(|options| Ok((options, Rounding::parse(&params.fix)?)));
+    if let Err(message) = options {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::bad_request(response_json));
+    }
+    let (options, fix) = options.unwrap();
+    let options = ParserOptions { all_errors: true, ..options };
+
+    // ------------------------------------------------------------
+    // Extract the recipient list from the body: JSON, NDJSON or a form with the CSV file
+    // ------------------------------------------------------------
+
+    if let Some(format) = vercel_body_format(&req) {
+        let options = ParserOptions { format, ..options };
+        return response::to_vercel(handler(options, fix, req.body().as_ref()));
+    }
+
+    let data = vercel_multipart(&req);
+    if let Err(message) = data {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::bad_request(response_json));
+    }
+
+    let mut data = data.unwrap();
+    let file = data.read_entry();
+    if let Err(error) = file {
+        let response_json = json!(GeneralErrorResponse { message: error.to_string() });
+
+        return response::to_vercel(response::bad_request(response_json));
+    }
+
+    match file.unwrap() {
+        Some(file) => {
+            let content_type = file.headers.content_type.as_ref().map(|mime| mime.to_string());
+            let options = options_for_upload(&options, file.headers.filename.as_deref(), content_type.as_deref());
+            response::to_vercel(handler(options, fix, file.data))
+        }
+        None => {
+            let response_json =
+                json!(GeneralErrorResponse { message: String::from("Invalid form data, missing file") });
+
+            response::to_vercel(response::bad_request(response_json))
+        }
+    }
+}
+
+/// Bind the route with the handler for the Warp handler.
+pub fn build_route() -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "create" / "preview")
+        .and(warp::post())
+        .and(warp::query::query::<Create>())
+        .and(with_recipients())
+        .and_then(handler_to_warp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::http::StatusCode;
+
+    fn options() -> ParserOptions {
+        ParserOptions { decimals: Some(2), all_errors: true, ..Default::default() }
+    }
+
+    #[test]
+    fn handler_valid_csv() {
+        let csv_data = b"address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0\n0xf31b00e025584486f7c37Cf0AE0073c97c12c634,1";
+        let response = handler(options(), None, &csv_data[..]);
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        assert_eq!(response.message["valid"], true);
+        assert_eq!(response.message["total"], "30100");
+        assert_eq!(response.message["recipients"], "3");
+        assert_eq!(response.message["depth"], 2);
+        assert_eq!(response.message["root"].as_str().unwrap().len(), 66);
+    }
+
+    #[test]
+    fn handler_reports_every_error() {
+        let rows: String = (0..150).map(|_| "0xThisIsNotAnAddress,1\n").collect();
+        let csv_data = format!("address,amount\n{}", rows);
+        let response = handler(options(), None, csv_data.as_bytes());
+
+        assert_eq!(response.status, StatusCode::OK.as_u16());
+        assert_eq!(response.message["valid"], false);
+        assert_eq!(response.message["errors"].as_array().unwrap().len(), 150);
+        assert!(response.message.get("root").is_none());
+    }
+
+    #[test]
+    fn handler_reports_warnings_of_valid_csv() {
+        let csv_data = b"address,amount\n0x000000000000000000000000000000000000dEaD,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
+        let response = handler(options(), None, &csv_data[..]);
+
+        assert_eq!(response.message["valid"], true);
+        assert_eq!(response.message["warnings"][0]["row"], 2);
+        assert_eq!(response.message["warnings"][0]["column"], "A");
+        assert_eq!(response.message["warnings"][0]["rule"], "burn-address");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn route_does_not_upload() {
+        let body = "--boundary\r\nContent-Disposition: form-data; name=\"data\"; filename=\"data.csv\"\r\n\r\naddress,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200\r\n--boundary--\r\n";
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create/preview?decimals=raw")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&build_route())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(message["valid"], true);
+        assert_eq!(message["total"], "300");
+        assert!(message.get("cid").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn route_returns_the_cleaned_csv() {
+        let body = "--boundary\r\nContent-Disposition: form-data; name=\"data\"; filename=\"data.csv\"\r\n\r\naddress,amount\n 0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.129\nf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200\n0xThisIsNotAnAddress,1\r\n--boundary--\r\n";
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/create/preview?decimals=2&fix=truncate")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&build_route())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(message["valid"], true);
+        assert_eq!(message["total"], "30012");
+        assert_eq!(
+            message["fixed"]["csv"],
+            "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.12\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200\n"
+        );
+        let actions: Vec<&str> =
+            message["fixed"]["changes"].as_array().unwrap().iter().map(|c| c["action"].as_str().unwrap()).collect();
+        assert_eq!(actions, vec!["trim", "truncate", "address-prefix", "drop-row"]);
+    }
+}
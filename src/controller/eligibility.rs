@@ -1,49 +1,42 @@
 use crate::{
+    controller::is_authorized,
     data_objects::{
-        dto::PersistentCampaignDto,
+        dto::{PersistentCampaignDto, ShardedCampaignDto},
         query_param::Eligibility,
         response::{self, EligibilityResponse, GeneralErrorResponse},
     },
-    services::ipfs::download_from_ipfs,
+    services::{
+        cache::{CampaignCache, CampaignFile},
+        store::{self, CampaignStore, StoreResult},
+    },
+    utils::{merkle::MerkleTreeDump, sharding::shard_key},
     WebResult,
 };
-use merkle_tree_rs::standard::{LeafType, StandardMerkleTree, StandardMerkleTreeData};
 
 use serde_json::json;
-use std::{collections::HashMap, str};
+use std::{collections::HashMap, str, sync::Arc};
 use url::Url;
 
 use vercel_runtime as Vercel;
 use warp::Filter;
 
-/// Bearer token guard
-fn is_authorized(req: &Vercel::Request) -> bool {
-    let headers = req.headers();
-    let expected_token = std::env::var("MERKLE_API_BEARER_TOKEN").expect("MERKLE_API_BEARER_TOKEN must be set");
-
-    if let Some(auth_header) = headers.get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            return auth_str == format!("Bearer {}", expected_token);
-        }
-    }
-
-    false
-}
-
 /// Eligibility request common handler. It downloads data from IPFS and determines if an address is eligible for an
-/// airstream campaign.
-pub async fn handler(eligibility: Eligibility) -> response::R {
-    let ipfs_data = download_from_ipfs::<PersistentCampaignDto>(&eligibility.cid).await;
-    if ipfs_data.is_err() {
+/// airstream campaign. For sharded campaigns only the manifest and the shard holding the address are downloaded.
+pub async fn handler(eligibility: Eligibility, store: &dyn CampaignStore, cache: &CampaignCache) -> response::R {
+    let file = cache.load_file(store, &eligibility.cid).await;
+    if file.is_err() {
         let response_json = json!(GeneralErrorResponse {
             message: "There was a problem processing your request: Bad CID provided".to_string(),
         });
 
         return response::internal_server_error(response_json);
     }
-    let ipfs_data = ipfs_data.unwrap();
-    let recipient_index =
-        ipfs_data.recipients.iter().position(|r| r.address.to_lowercase() == eligibility.address.to_lowercase());
+    let loaded = match file.unwrap() {
+        CampaignFile::Single(loaded) => loaded,
+        CampaignFile::Sharded(manifest) => return sharded_handler(&manifest, &eligibility.address, store, cache).await,
+    };
+    let ipfs_data = &loaded.campaign;
+    let recipient_index = loaded.index_of(&eligibility.address);
 
     if recipient_index.is_none() {
         let response_json = json!(GeneralErrorResponse {
@@ -55,24 +48,109 @@ pub async fn handler(eligibility: Eligibility) -> response::R {
 
     let recipient_index = recipient_index.unwrap();
 
-    let tree_data: StandardMerkleTreeData = serde_json::from_str(&ipfs_data.merkle_tree).unwrap();
+    let tree = loaded.tree();
+    if tree.is_err() {
+        let response_json = json!(GeneralErrorResponse {
+            message: "There was a problem processing your request: Invalid campaign file".to_string(),
+        });
+
+        return response::internal_server_error(response_json);
+    }
 
-    let tree = StandardMerkleTree::load(tree_data);
+    let eligibility = eligibility_of(ipfs_data, tree.unwrap(), recipient_index);
+    if eligibility.is_err() {
+        let response_json = json!(GeneralErrorResponse {
+            message: "There was a problem processing your request: Invalid campaign file".to_string(),
+        });
 
-    let proof = tree.get_proof(LeafType::Number(recipient_index));
+        return response::internal_server_error(response_json);
+    }
 
-    let response_json = json!(&EligibilityResponse {
-        index: recipient_index,
-        proof,
-        address: ipfs_data.recipients[recipient_index].address.clone(),
-        amount: ipfs_data.recipients[recipient_index].amount.clone(),
-    });
+    let response_json = json!(&eligibility.unwrap());
     response::ok(response_json)
 }
 
+/// Determine if an address is eligible for a sharded airstream campaign.
+async fn sharded_handler(
+    manifest: &ShardedCampaignDto,
+    address: &str,
+    store: &dyn CampaignStore,
+    cache: &CampaignCache,
+) -> response::R {
+    let eligibility = sharded_eligibility_of(manifest, address, store, cache).await;
+    if eligibility.is_err() {
+        let response_json = json!(GeneralErrorResponse {
+            message: "There was a problem processing your request: Invalid campaign shard".to_string(),
+        });
+
+        return response::internal_server_error(response_json);
+    }
+
+    match eligibility.unwrap() {
+        Some(eligibility) => response::ok(json!(&eligibility)),
+        None => {
+            let response_json = json!(GeneralErrorResponse {
+                message: String::from("The provided address is not eligible for this campaign"),
+            });
+
+            response::bad_request(response_json)
+        }
+    }
+}
+
+/// Build the eligibility details of an address inside a sharded campaign, downloading only the shard that would hold
+/// it. Returns `None` when the address is not eligible.
+pub(crate) async fn sharded_eligibility_of(
+    manifest: &ShardedCampaignDto,
+    address: &str,
+    store: &dyn CampaignStore,
+    cache: &CampaignCache,
+) -> StoreResult<Option<EligibilityResponse>> {
+    let shard_cid = manifest.shards.get(&shard_key(address, manifest.prefix_length));
+    if shard_cid.is_none() {
+        return Ok(None);
+    }
+
+    let loaded = cache.load_shard(store, shard_cid.unwrap()).await?;
+    if loaded.shard.root != manifest.root {
+        return Err("The shard does not belong to the campaign".into());
+    }
+
+    Ok(loaded.index_of(address).map(|i| {
+        let recipient = &loaded.shard.recipients[i];
+        EligibilityResponse {
+            index: recipient.index,
+            proof: recipient.proof.clone(),
+            address: recipient.address.clone(),
+            amount: recipient.amount.clone(),
+            leaf: recipient.leaf.clone(),
+        }
+    }))
+}
+
+/// Build the eligibility details, proof and leaf included, of the recipient found at the specified index of the
+/// campaign.
+pub(crate) fn eligibility_of(
+    campaign: &PersistentCampaignDto,
+    tree: &MerkleTreeDump,
+    index: usize,
+) -> Result<EligibilityResponse, String> {
+    Ok(EligibilityResponse {
+        index,
+        proof: tree.proof(index)?,
+        address: campaign.recipients[index].address.clone(),
+        amount: campaign.recipients[index].amount.clone(),
+        leaf: tree.values[index].value.clone(),
+    })
+}
+
 /// Warp specific handler for the eligibility endpoint
-pub async fn handler_to_warp(eligibility: Eligibility) -> WebResult<impl warp::Reply> {
-    let result = handler(eligibility).await;
+pub async fn handler_to_warp(
+    eligibility: Eligibility,
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> WebResult<impl warp::Reply> {
+    let result = handler(eligibility, store.as_ref(), &cache).await;
     Ok(response::to_warp(result))
 }
 
@@ -96,7 +174,14 @@ pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<
             cid: query.get("cid").unwrap_or(&fallback).clone(),
         };
 
-        let result = handler(params).await;
+        let store = store::from_env();
+        if let Err(message) = store {
+            let response_json = json!(GeneralErrorResponse { message });
+
+            return response::to_vercel(response::internal_server_error(response_json));
+        }
+
+        let result = handler(params, store.unwrap().as_ref(), CampaignCache::shared()).await;
 
         response::to_vercel(result)
     } else {
@@ -108,17 +193,25 @@ pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<
 }
 
 /// Bind the route with the handler for the Warp handler.
-pub fn build_route() -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+pub fn build_route(
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::path!("api" / "eligibility")
         .and(warp::get())
         .and(warp::query::query::<Eligibility>())
+        .and(super::with_store(store))
+        .and(super::with_cache(cache))
         .and_then(handler_to_warp)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::async_test::{setup_env_vars, SERVER};
+    use crate::{
+        services::{cache::DEFAULT_CACHE_MAX_BYTES, store::pinata::PinataStore},
+        utils::async_test::{setup_env_vars, SERVER},
+    };
 
     #[tokio::test]
     async fn handler_success_response() {
@@ -135,7 +228,7 @@ mod tests {
             cid: "valid_cid".to_string(),
             address: "0x0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491".to_string(),
         };
-        let response = handler(validity).await;
+        let response = handler(validity, &PinataStore::from_env(), &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
         assert_eq!(response.status, warp::http::StatusCode::OK.as_u16());
         mock.assert();
         drop(server);
@@ -156,7 +249,7 @@ mod tests {
             cid: "invalid_cid".to_string(),
             address: "0x0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491".to_string(),
         };
-        let response = handler(validity).await;
+        let response = handler(validity, &PinataStore::from_env(), &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
         assert_eq!(response.status, warp::http::StatusCode::INTERNAL_SERVER_ERROR.as_u16());
         mock.assert();
         drop(server);
@@ -0,0 +1,271 @@
+use crate::{
+    controller::{
+        eligibility::{eligibility_of, sharded_eligibility_of},
+        is_authorized,
+    },
+    data_objects::{
+        dto::{BatchEligibilityDto, ShardedCampaignDto},
+        query_param::BatchEligibility,
+        response::{self, BatchEligibilityEntry, BatchEligibilityResponse, GeneralErrorResponse},
+    },
+    services::{
+        cache::{CampaignCache, CampaignFile},
+        store::{self, CampaignStore},
+    },
+    WebResult,
+};
+use bytes::Bytes;
+use csv::ReaderBuilder;
+
+use serde_json::json;
+use std::{collections::HashMap, str, sync::Arc};
+use url::Url;
+
+use vercel_runtime as Vercel;
+use warp::Filter;
+
+/// Maximum number of addresses that can be checked through a single batch eligibility request
+pub const MAX_BATCH_SIZE: usize = 10_000;
+
+/// Extract the batch eligibility request from the request body. The body is either a JSON document containing the
+/// `cid` and the `addresses` or, when the content type is `text/csv`, a CSV file with an `address` column. In the
+/// latter case the CID is provided through the query parameters.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::controller::eligibility_batch::parse_body;
+///
+/// let csv_body = b"address\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc";
+/// let request = parse_body(Some("text/csv"), "valid_cid", csv_body).unwrap();
+/// assert_eq!(request.cid, "valid_cid");
+/// assert_eq!(request.addresses.len(), 2);
+///
+/// let json_body = br#"{"cid": "valid_cid", "addresses": ["0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491"]}"#;
+/// let request = parse_body(Some("application/json"), "", json_body).unwrap();
+/// assert_eq!(request.addresses.len(), 1);
+/// ```
+pub fn parse_body(content_type: Option<&str>, cid: &str, body: &[u8]) -> Result<BatchEligibilityDto, String> {
+    let is_csv = content_type.map(|c| c.to_lowercase().starts_with("text/csv")).unwrap_or(false);
+
+    if !is_csv {
+        let mut request: BatchEligibilityDto =
+            serde_json::from_slice(body).map_err(|error| format!("Invalid request body: {}", error))?;
+        if request.cid.is_empty() {
+            request.cid = cid.to_string();
+        }
+        return Ok(request);
+    }
+
+    let mut rdr = ReaderBuilder::new().flexible(true).from_reader(body);
+    let header = rdr.headers().map_err(|error| format!("Invalid csv file: {}", error))?;
+    let column = header
+        .iter()
+        .position(|h| h.trim().to_lowercase() == "address")
+        .ok_or_else(|| String::from("The csv file should contain an `address` column"))?;
+
+    let mut addresses = Vec::new();
+    for (row_index, result) in rdr.records().enumerate() {
+        let record = result.map_err(|_| format!("Invalid row {}", row_index + 2))?;
+        if let Some(address) = record.get(column) {
+            addresses.push(address.trim().to_string());
+        }
+    }
+
+    Ok(BatchEligibilityDto { cid: cid.to_string(), addresses })
+}
+
+/// Batch eligibility request common handler. It downloads the campaign once and determines, for every provided
+/// address, if it is eligible for the airstream campaign.
+pub async fn handler(request: BatchEligibilityDto, store: &dyn CampaignStore, cache: &CampaignCache) -> response::R {
+    if request.addresses.is_empty() || request.addresses.len() > MAX_BATCH_SIZE {
+        let response_json = json!(GeneralErrorResponse {
+            message: format!("The request should contain between 1 and {} addresses", MAX_BATCH_SIZE),
+        });
+
+        return response::bad_request(response_json);
+    }
+
+    let file = cache.load_file(store, &request.cid).await;
+    if file.is_err() {
+        let response_json = json!(GeneralErrorResponse {
+            message: "There was a problem processing your request: Bad CID provided".to_string(),
+        });
+
+        return response::internal_server_error(response_json);
+    }
+    let loaded = match file.unwrap() {
+        CampaignFile::Single(loaded) => loaded,
+        CampaignFile::Sharded(manifest) => return sharded_handler(request, &manifest, store, cache).await,
+    };
+    let ipfs_data = &loaded.campaign;
+
+    let tree = loaded.tree();
+    if tree.is_err() {
+        let response_json = json!(GeneralErrorResponse {
+            message: "There was a problem processing your request: Invalid campaign file".to_string(),
+        });
+
+        return response::internal_server_error(response_json);
+    }
+    let tree = tree.unwrap();
+
+    let results = request
+        .addresses
+        .into_iter()
+        .map(|address| {
+            let eligibility = loaded.index_of(&address).and_then(|i| eligibility_of(ipfs_data, tree, i).ok());
+            BatchEligibilityEntry { address, eligible: eligibility.is_some(), eligibility }
+        })
+        .collect();
+
+    let response_json = json!(&BatchEligibilityResponse { cid: request.cid, results });
+    response::ok(response_json)
+}
+
+/// Determine the eligibility of every provided address for a sharded airstream campaign. The shards are downloaded
+/// through the cache, so each of them is fetched at most once per request while it stays cached.
+async fn sharded_handler(
+    request: BatchEligibilityDto,
+    manifest: &ShardedCampaignDto,
+    store: &dyn CampaignStore,
+    cache: &CampaignCache,
+) -> response::R {
+    let mut results = Vec::with_capacity(request.addresses.len());
+    for address in request.addresses {
+        let eligibility = sharded_eligibility_of(manifest, &address, store, cache).await;
+        if eligibility.is_err() {
+            let response_json = json!(GeneralErrorResponse {
+                message: "There was a problem processing your request: Invalid campaign shard".to_string(),
+            });
+
+            return response::internal_server_error(response_json);
+        }
+
+        let eligibility = eligibility.unwrap();
+        results.push(BatchEligibilityEntry { address, eligible: eligibility.is_some(), eligibility });
+    }
+
+    let response_json = json!(&BatchEligibilityResponse { cid: request.cid, results });
+    response::ok(response_json)
+}
+
+/// Warp specific handler for the batch eligibility endpoint
+pub async fn handler_to_warp(
+    params: BatchEligibility,
+    content_type: Option<String>,
+    body: Bytes,
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> WebResult<impl warp::Reply> {
+    let request = parse_body(content_type.as_deref(), &params.cid, &body);
+    if let Err(message) = request {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return Ok(response::to_warp(response::bad_request(response_json)));
+    }
+
+    let result = handler(request.unwrap(), store.as_ref(), &cache).await;
+    Ok(response::to_warp(result))
+}
+
+/// Vercel specific handler for the batch eligibility endpoint
+pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<Vercel::Body>, Vercel::Error> {
+    if !is_authorized(&req) {
+        let response_json =
+            json!(GeneralErrorResponse { message: String::from("Bad authentication process provided.") });
+
+        return response::to_vercel(response::unauthorized(response_json));
+    }
+
+    // ------------------------------------------------------------
+    // Extract query parameters from the URL: cid
+    // ------------------------------------------------------------
+
+    let url = Url::parse(&req.uri().to_string()).unwrap();
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let fallback = String::from("");
+    let cid = query.get("cid").unwrap_or(&fallback);
+
+    // ------------------------------------------------------------
+    // Extract the addresses from the body
+    // ------------------------------------------------------------
+
+    let content_type = req.headers().get("content-type").and_then(|v| v.to_str().ok());
+    let request = parse_body(content_type, cid, req.body());
+    if let Err(message) = request {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::bad_request(response_json));
+    }
+
+    let store = store::from_env();
+    if let Err(message) = store {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::internal_server_error(response_json));
+    }
+
+    let result = handler(request.unwrap(), store.unwrap().as_ref(), CampaignCache::shared()).await;
+    response::to_vercel(result)
+}
+
+/// Bind the route with the handler for the Warp handler.
+pub fn build_route(
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "eligibility" / "batch")
+        .and(warp::post())
+        .and(warp::query::query::<BatchEligibility>())
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::body::content_length_limit(10_000_000))
+        .and(warp::body::bytes())
+        .and(super::with_store(store))
+        .and(super::with_cache(cache))
+        .and_then(handler_to_warp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{cache::DEFAULT_CACHE_MAX_BYTES, store::memory::MemoryStore};
+
+    const CAMPAIGN: &str = r#"{"root": "root", "total_amount": "10", "number_of_recipients": 1, "merkle_tree":"{\"format\":\"standard-v1\",\"tree\":[\"0x23bb7a869a407bc69b27975acff039dfe6a6abe5e3da626e98623d70137eb320\"],\"values\":[{\"value\":[\"0\",\"0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491\",\"5000\"],\"tree_index\":0}],\"leaf_encoding\":[\"uint\",\"address\",\"uint256\"]}", "recipients": [{ "address": "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", "amount": "5000"}]}"#;
+
+    #[tokio::test]
+    async fn handler_success_response() {
+        let store = MemoryStore::default();
+        let cid = store.put(CAMPAIGN.as_bytes().to_vec()).await.unwrap();
+
+        let request = BatchEligibilityDto {
+            cid,
+            addresses: vec![
+                "0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491".to_string(),
+                "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc".to_string(),
+            ],
+        };
+        let response = handler(request, &store, &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
+
+        assert_eq!(response.status, warp::http::StatusCode::OK.as_u16());
+        assert_eq!(response.message["results"][0]["eligible"], true);
+        assert_eq!(response.message["results"][0]["eligibility"]["amount"], "5000");
+        assert_eq!(response.message["results"][1]["eligible"], false);
+        assert!(response.message["results"][1]["eligibility"].is_null());
+    }
+
+    #[tokio::test]
+    async fn handler_error_response() {
+        let store = MemoryStore::default();
+        let request = BatchEligibilityDto { cid: "invalid_cid".to_string(), addresses: vec!["0x9ad7".to_string()] };
+
+        let response = handler(request, &store, &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
+        assert_eq!(response.status, warp::http::StatusCode::INTERNAL_SERVER_ERROR.as_u16());
+    }
+
+    #[test]
+    fn parse_csv_body_without_address_column() {
+        let result = parse_body(Some("text/csv"), "valid_cid", b"wallet\n0x9ad7");
+        assert!(result.is_err());
+    }
+}
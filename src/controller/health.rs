@@ -1,36 +1,42 @@
-use crate::{data_objects::response, WebResult};
+use crate::{data_objects::response, services::cache::CampaignCache, WebResult};
 use serde_json::json;
-use std::str;
+use std::{str, sync::Arc};
 
 use vercel_runtime as Vercel;
 use warp::Filter;
 
-/// Health request common handler. Returns an hardcoded message in order to display that the server works properly.
-pub async fn handler() -> response::R {
+/// Health request common handler. Returns an hardcoded message in order to display that the server works properly,
+/// together with the usage of the campaign cache when the server keeps one.
+pub async fn handler(cache: Option<&CampaignCache>) -> response::R {
     const MESSAGE: &str = "Server up and running";
 
-    let result = json!({
+    let mut result = json!({
         "status": "success".to_string(),
         "message": MESSAGE.to_string(),
     });
+    if let Some(cache) = cache {
+        result["cache"] = json!(cache.stats());
+    }
 
     response::ok(result)
 }
 
 /// Warp specific handler for the health endpoint
-pub async fn handler_to_warp() -> WebResult<impl warp::Reply> {
-    let result = handler().await;
+pub async fn handler_to_warp(cache: Arc<CampaignCache>) -> WebResult<impl warp::Reply> {
+    let result = handler(Some(&cache)).await;
     Ok(response::to_warp(result))
 }
 
 /// Vercel specific handler for the health endpoint
 pub async fn handler_to_vercel() -> Result<Vercel::Response<Vercel::Body>, Vercel::Error> {
-    let result = handler().await;
+    let result = handler(None).await;
 
     response::to_vercel(result)
 }
 
 /// Bind the route with the handler for the Warp handler.
-pub fn build_route() -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    warp::path!("api" / "health").and(warp::get()).and_then(handler_to_warp)
+pub fn build_route(
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "health").and(warp::get()).and(super::with_cache(cache)).and_then(handler_to_warp)
 }
@@ -0,0 +1,222 @@
+use crate::{
+    controller::is_authorized,
+    data_objects::{
+        dto::MultiProofDto,
+        response::{self, GeneralErrorResponse, MultiProofResponse},
+    },
+    services::{
+        cache::{CampaignCache, CampaignFile},
+        store::{self, CampaignStore},
+    },
+    WebResult,
+};
+
+use serde_json::json;
+use std::{str, sync::Arc};
+
+use vercel_runtime as Vercel;
+use warp::Filter;
+
+/// Multiproof request common handler. It downloads the campaign and builds a single proof for all the selected
+/// recipients, so that they can be claimed at once.
+pub async fn handler(request: MultiProofDto, store: &dyn CampaignStore, cache: &CampaignCache) -> response::R {
+    if request.addresses.is_empty() && request.indexes.is_empty() {
+        let response_json = json!(GeneralErrorResponse {
+            message: String::from("The request should contain at least one address or index"),
+        });
+
+        return response::bad_request(response_json);
+    }
+
+    let file = cache.load_file(store, &request.cid).await;
+    if file.is_err() {
+        let response_json = json!(GeneralErrorResponse {
+            message: "There was a problem processing your request: Bad CID provided".to_string(),
+        });
+
+        return response::internal_server_error(response_json);
+    }
+    let loaded = match file.unwrap() {
+        CampaignFile::Single(loaded) => loaded,
+        CampaignFile::Sharded(_) => {
+            let response_json = json!(GeneralErrorResponse {
+                message: String::from("Multiproofs are not available for sharded campaigns"),
+            });
+
+            return response::bad_request(response_json);
+        }
+    };
+    let ipfs_data = &loaded.campaign;
+
+    if loaded.tree().is_err() {
+        let response_json = json!(GeneralErrorResponse {
+            message: "There was a problem processing your request: Invalid campaign file".to_string(),
+        });
+
+        return response::internal_server_error(response_json);
+    }
+
+    let mut indexes = request.indexes;
+    for address in request.addresses {
+        match loaded.index_of(&address) {
+            Some(index) => indexes.push(index),
+            None => {
+                let response_json = json!(GeneralErrorResponse {
+                    message: format!("The address {} is not eligible for this campaign", address),
+                });
+
+                return response::bad_request(response_json);
+            }
+        }
+    }
+
+    let multi_proof = ipfs_data.merkle_tree.multi_proof(&indexes);
+    if let Err(message) = multi_proof {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::bad_request(response_json);
+    }
+    let multi_proof = multi_proof.unwrap();
+
+    let response_json = json!(&MultiProofResponse {
+        root: ipfs_data.root.clone(),
+        leaves: multi_proof.leaves,
+        proof: multi_proof.proof,
+        proof_flags: multi_proof.proof_flags,
+    });
+    response::ok(response_json)
+}
+
+/// Warp specific handler for the multiproof endpoint
+pub async fn handler_to_warp(
+    request: MultiProofDto,
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> WebResult<impl warp::Reply> {
+    let result = handler(request, store.as_ref(), &cache).await;
+    Ok(response::to_warp(result))
+}
+
+/// Vercel specific handler for the multiproof endpoint
+pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<Vercel::Body>, Vercel::Error> {
+    if !is_authorized(&req) {
+        let response_json =
+            json!(GeneralErrorResponse { message: String::from("Bad authentication process provided.") });
+
+        return response::to_vercel(response::unauthorized(response_json));
+    }
+
+    let request = serde_json::from_slice::<MultiProofDto>(req.body());
+    if let Err(error) = request {
+        let response_json = json!(GeneralErrorResponse { message: format!("Invalid request body: {}", error) });
+
+        return response::to_vercel(response::bad_request(response_json));
+    }
+
+    let store = store::from_env();
+    if let Err(message) = store {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::internal_server_error(response_json));
+    }
+
+    let result = handler(request.unwrap(), store.unwrap().as_ref(), CampaignCache::shared()).await;
+    response::to_vercel(result)
+}
+
+/// Bind the route with the handler for the Warp handler.
+pub fn build_route(
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "multiproof")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(10_000_000))
+        .and(warp::body::json::<MultiProofDto>())
+        .and(super::with_store(store))
+        .and(super::with_cache(cache))
+        .and_then(handler_to_warp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::leaf_schema::LeafSchema;
+    use crate::{
+        data_objects::dto::{PersistentCampaignDto, RecipientDto, CAMPAIGN_SCHEMA_VERSION},
+        services::{
+            cache::DEFAULT_CACHE_MAX_BYTES,
+            store::{memory::MemoryStore, put_json},
+        },
+    };
+    use merkle_tree_rs::standard::StandardMerkleTree;
+
+    const ADDRESSES: [&str; 3] = [
+        "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491",
+        "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc",
+        "0xf31b00e025584486f7c37Cf0AE0073c97c12c634",
+    ];
+
+    async fn create_campaign(store: &MemoryStore) -> String {
+        let leaves = ADDRESSES.iter().enumerate().map(|(i, a)| vec![i.to_string(), a.to_string(), "100".to_string()]);
+        let tree = StandardMerkleTree::of(
+            leaves.collect(),
+            &["uint".to_string(), "address".to_string(), "uint256".to_string()],
+        );
+        let campaign = PersistentCampaignDto {
+            version: CAMPAIGN_SCHEMA_VERSION,
+            total_amount: "300".to_string(),
+            number_of_recipients: 3,
+            root: tree.root(),
+            merkle_tree: serde_json::from_value(json!(tree.dump())).unwrap(),
+            leaf_schema: LeafSchema::default(),
+            recipients: ADDRESSES
+                .iter()
+                .map(|a| RecipientDto { address: a.to_string(), amount: "100".to_string() })
+                .collect(),
+            address_index: None,
+        };
+        put_json(store, &campaign).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn handler_success_response() {
+        let store = MemoryStore::default();
+        let cid = create_campaign(&store).await;
+
+        let request = MultiProofDto { cid, addresses: vec![ADDRESSES[0].to_lowercase()], indexes: vec![2] };
+        let response = handler(request, &store, &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
+
+        assert_eq!(response.status, warp::http::StatusCode::OK.as_u16());
+        assert_eq!(response.message["leaves"].as_array().unwrap().len(), 2);
+        assert_eq!(response.message["proof"].as_array().unwrap().len(), 1);
+        assert_eq!(response.message["proofFlags"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn handler_not_eligible_address() {
+        let store = MemoryStore::default();
+        let cid = create_campaign(&store).await;
+
+        let request = MultiProofDto {
+            cid,
+            addresses: vec!["0x0000000000000000000000000000000000000001".to_string()],
+            indexes: vec![],
+        };
+        let response = handler(request, &store, &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
+
+        assert_eq!(response.status, warp::http::StatusCode::BAD_REQUEST.as_u16());
+    }
+
+    #[tokio::test]
+    async fn handler_duplicated_recipient() {
+        let store = MemoryStore::default();
+        let cid = create_campaign(&store).await;
+
+        let request = MultiProofDto { cid, addresses: vec![ADDRESSES[1].to_string()], indexes: vec![1] };
+        let response = handler(request, &store, &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
+
+        assert_eq!(response.status, warp::http::StatusCode::BAD_REQUEST.as_u16());
+        assert_eq!(response.message["message"], "Cannot prove duplicated index 1");
+    }
+}
@@ -1,15 +1,17 @@
 use crate::{
     data_objects::{
-        dto::PersistentCampaignDto,
         query_param::Validity,
         response::{self, GeneralErrorResponse, ValidResponse},
     },
-    services::ipfs::download_from_ipfs,
+    services::{
+        cache::{CampaignCache, CampaignFile},
+        store::{self, CampaignStore},
+    },
     WebResult,
 };
 
 use serde_json::json;
-use std::{collections::HashMap, str};
+use std::{collections::HashMap, str, sync::Arc};
 use url::Url;
 
 use vercel_runtime as Vercel;
@@ -17,28 +19,39 @@ use warp::Filter;
 
 /// Validity request common handler. It downloads data from IPFS and checks if it can be properly deserialized into a
 /// `PersistentCampaignDto` struct.
-pub async fn handler(validity: Validity) -> response::R {
-    let ipfs_data = download_from_ipfs::<PersistentCampaignDto>(&validity.cid).await;
-    if ipfs_data.is_err() {
+pub async fn handler(validity: Validity, store: &dyn CampaignStore, cache: &CampaignCache) -> response::R {
+    let file = cache.load_file(store, &validity.cid).await;
+    if file.is_err() {
         let response_json =
             json!(GeneralErrorResponse { message: "Bad CID or invalid file format provided.".to_string() });
 
         return response::internal_server_error(response_json);
     }
-    let ipfs_data = ipfs_data.unwrap();
-
-    let response_json = json!(&ValidResponse {
-        root: ipfs_data.root,
-        total: ipfs_data.total_amount,
-        recipients: ipfs_data.number_of_recipients.to_string(),
-        cid: validity.cid
-    });
+
+    let response_json = match file.unwrap() {
+        CampaignFile::Single(loaded) => json!(&ValidResponse {
+            root: loaded.campaign.root.clone(),
+            total: loaded.campaign.total_amount.clone(),
+            recipients: loaded.campaign.number_of_recipients.to_string(),
+            cid: validity.cid
+        }),
+        CampaignFile::Sharded(manifest) => json!(&ValidResponse {
+            root: manifest.root.clone(),
+            total: manifest.total_amount.clone(),
+            recipients: manifest.number_of_recipients.to_string(),
+            cid: validity.cid
+        }),
+    };
     response::ok(response_json)
 }
 
 /// Warp specific handler for the validity endpoint
-pub async fn handler_to_warp(validity: Validity) -> WebResult<impl warp::Reply> {
-    let result = handler(validity).await;
+pub async fn handler_to_warp(
+    validity: Validity,
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> WebResult<impl warp::Reply> {
+    let result = handler(validity, store.as_ref(), &cache).await;
     Ok(response::to_warp(result))
 }
 
@@ -58,20 +71,38 @@ pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<
     let fallback = String::from("");
     let params = Validity { cid: query.get("cid").unwrap_or(&fallback).clone() };
 
-    let result = handler(params).await;
+    let store = store::from_env();
+    if let Err(message) = store {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::internal_server_error(response_json));
+    }
+
+    let result = handler(params, store.unwrap().as_ref(), CampaignCache::shared()).await;
 
     response::to_vercel(result)
 }
 
 /// Bind the route with the handler for the Warp handler.
-pub fn build_route() -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    warp::path!("api" / "validity").and(warp::get()).and(warp::query::query::<Validity>()).and_then(handler_to_warp)
+pub fn build_route(
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "validity")
+        .and(warp::get())
+        .and(warp::query::query::<Validity>())
+        .and(super::with_store(store))
+        .and(super::with_cache(cache))
+        .and_then(handler_to_warp)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::async_test::{setup_env_vars, SERVER};
+    use crate::{
+        services::{cache::DEFAULT_CACHE_MAX_BYTES, store::pinata::PinataStore},
+        utils::async_test::{setup_env_vars, SERVER},
+    };
 
     #[tokio::test]
     async fn handler_success_response() {
@@ -86,7 +117,7 @@ mod tests {
             .create();
 
         let validity = Validity { cid: "valid_cid".to_string() };
-        let response = handler(validity).await;
+        let response = handler(validity, &PinataStore::from_env(), &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
         assert_eq!(response.status, warp::http::StatusCode::OK.as_u16());
         mock.assert();
         drop(server);
@@ -105,7 +136,7 @@ mod tests {
             .create();
 
         let validity = Validity { cid: "invalid_cid".to_string() };
-        let response = handler(validity).await;
+        let response = handler(validity, &PinataStore::from_env(), &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES)).await;
         assert_eq!(response.status, warp::http::StatusCode::INTERNAL_SERVER_ERROR.as_u16());
         mock.assert();
         drop(server);
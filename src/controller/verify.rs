@@ -0,0 +1,195 @@
+use crate::{
+    data_objects::{
+        dto::VerifyDto,
+        response::{self, GeneralErrorResponse, VerifyResponse},
+    },
+    services::{
+        cache::CampaignCache,
+        store::{self, CampaignStore},
+    },
+    utils::{
+        leaf_schema::LeafSchema,
+        merkle::{encode_node, leaf_hash, process_proof},
+    },
+    WebResult,
+};
+
+use serde_json::json;
+use std::{str, sync::Arc};
+
+use vercel_runtime as Vercel;
+use warp::Filter;
+
+/// Verify request common handler. It recomputes the `standard-v1` leaf of the recipient, folds the proof into a root
+/// and compares it against the expected root.
+pub async fn handler(request: VerifyDto, store: &dyn CampaignStore, cache: &CampaignCache) -> response::R {
+    let mut leaf_schema = None;
+    let expected_root = match (request.root, request.cid) {
+        (Some(root), _) => root,
+        (None, Some(cid)) => {
+            let file = cache.load_file(store, &cid).await;
+            if file.is_err() {
+                let response_json = json!(GeneralErrorResponse {
+                    message: "There was a problem processing your request: Bad CID provided".to_string(),
+                });
+
+                return response::internal_server_error(response_json);
+            }
+            let file = file.unwrap();
+            leaf_schema = Some(file.leaf_schema().clone());
+            file.root().to_string()
+        }
+        (None, None) => {
+            let response_json =
+                json!(GeneralErrorResponse { message: String::from("Either the root or the cid should be provided") });
+
+            return response::bad_request(response_json);
+        }
+    };
+
+    let leaf_schema = match leaf_schema {
+        Some(leaf_schema) => Ok(leaf_schema),
+        None => LeafSchema::parse(&request.leaf_encoding),
+    };
+    if let Err(message) = leaf_schema {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::bad_request(response_json);
+    }
+    let leaf_schema = leaf_schema.unwrap();
+
+    let mut extra = Vec::new();
+    for field in leaf_schema.extra_fields() {
+        match request.fields.get(&field.name) {
+            Some(value) => extra.push(value.trim().to_string()),
+            None => {
+                let response_json = json!(GeneralErrorResponse {
+                    message: format!("Missing value for the leaf field `{}`", field.name)
+                });
+
+                return response::bad_request(response_json);
+            }
+        }
+    }
+
+    let values = leaf_schema.leaf_values(request.index, request.address.trim(), request.amount.trim(), &extra);
+    let leaf = leaf_hash(&values, &leaf_schema.types());
+    if let Err(message) = leaf {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::bad_request(response_json);
+    }
+    let leaf = leaf.unwrap();
+
+    let computed_root = process_proof(leaf, &request.proof);
+    if let Err(message) = computed_root {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::bad_request(response_json);
+    }
+    let computed_root = computed_root.unwrap();
+
+    let response_json = json!(&VerifyResponse {
+        valid: computed_root.to_lowercase() == expected_root.to_lowercase(),
+        leaf: encode_node(&leaf),
+        computed_root,
+        expected_root,
+    });
+    response::ok(response_json)
+}
+
+/// Warp specific handler for the verify endpoint
+pub async fn handler_to_warp(
+    request: VerifyDto,
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> WebResult<impl warp::Reply> {
+    let result = handler(request, store.as_ref(), &cache).await;
+    Ok(response::to_warp(result))
+}
+
+/// Vercel specific handler for the verify endpoint
+pub async fn handler_to_vercel(req: Vercel::Request) -> Result<Vercel::Response<Vercel::Body>, Vercel::Error> {
+    let request = serde_json::from_slice::<VerifyDto>(req.body());
+    if let Err(error) = request {
+        let response_json = json!(GeneralErrorResponse { message: format!("Invalid request body: {}", error) });
+
+        return response::to_vercel(response::bad_request(response_json));
+    }
+
+    let store = store::from_env();
+    if let Err(message) = store {
+        let response_json = json!(GeneralErrorResponse { message });
+
+        return response::to_vercel(response::internal_server_error(response_json));
+    }
+
+    let result = handler(request.unwrap(), store.unwrap().as_ref(), CampaignCache::shared()).await;
+    response::to_vercel(result)
+}
+
+/// Bind the route with the handler for the Warp handler.
+pub fn build_route(
+    store: Arc<dyn CampaignStore>,
+    cache: Arc<CampaignCache>,
+) -> impl warp::Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("api" / "verify")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1_000_000))
+        .and(warp::body::json::<VerifyDto>())
+        .and(super::with_store(store))
+        .and(super::with_cache(cache))
+        .and_then(handler_to_warp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{cache::DEFAULT_CACHE_MAX_BYTES, store::memory::MemoryStore};
+    use std::collections::HashMap;
+
+    const ROOT: &str = "0x23bb7a869a407bc69b27975acff039dfe6a6abe5e3da626e98623d70137eb320";
+
+    fn create_request(amount: &str) -> VerifyDto {
+        VerifyDto {
+            root: Some(ROOT.to_string()),
+            cid: None,
+            index: 0,
+            address: "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491".to_string(),
+            amount: amount.to_string(),
+            proof: vec![],
+            leaf_encoding: String::new(),
+            fields: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn handler_valid_proof() {
+        let response =
+            handler(create_request("5000"), &MemoryStore::default(), &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES))
+                .await;
+
+        assert_eq!(response.status, warp::http::StatusCode::OK.as_u16());
+        assert_eq!(response.message["valid"], true);
+        assert_eq!(response.message["computed_root"], ROOT);
+    }
+
+    #[tokio::test]
+    async fn handler_invalid_proof() {
+        let response =
+            handler(create_request("5001"), &MemoryStore::default(), &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES))
+                .await;
+
+        assert_eq!(response.status, warp::http::StatusCode::OK.as_u16());
+        assert_eq!(response.message["valid"], false);
+    }
+
+    #[tokio::test]
+    async fn handler_invalid_amount() {
+        let response =
+            handler(create_request("50.01"), &MemoryStore::default(), &CampaignCache::new(DEFAULT_CACHE_MAX_BYTES))
+                .await;
+
+        assert_eq!(response.status, warp::http::StatusCode::BAD_REQUEST.as_u16());
+    }
+}
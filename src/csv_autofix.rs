@@ -0,0 +1,351 @@
+use csv::{StringRecord, WriterBuilder};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::{collections::HashMap, error::Error, io::Read};
+
+use crate::{
+    csv_campaign_parser::{CampaignCsvParsed, Duplicates, ParserOptions, RecordValidator},
+    utils::{
+        csv_dialect::{open_csv, CsvDialect},
+        csv_validator::{column_name, map_csv_header},
+    },
+};
+
+type ParserError = Box<dyn Error + Send + Sync>;
+
+/// Addresses written without their `0x` prefix, or with an uppercase one
+static UNPREFIXED_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(0X)?[0-9a-fA-F]{40}$").unwrap());
+
+/// Amounts whose thousands are separated by commas, underscores, apostrophes or spaces. The commas are only stripped
+/// from the files delimited by commas or tabs, see `autofix_csv`.
+static THOUSANDS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\+?\d{1,3}([,_' ]\d{3})+(\.\d*)?$").unwrap());
+
+/// How the auto-fix pass reduces the amounts having more decimals than the token
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rounding {
+    /// Round the amounts half up
+    Round,
+    /// Drop the excess decimals
+    Truncate,
+}
+
+impl Rounding {
+    /// Parse the auto-fix mode provided through the query parameters. The auto-fix pass is off when it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::csv_autofix::Rounding;
+    ///
+    /// assert_eq!(Rounding::parse(""), Ok(None));
+    /// assert_eq!(Rounding::parse("Truncate"), Ok(Some(Rounding::Truncate)));
+    /// assert!(Rounding::parse("floor").is_err());
+    /// ```
+    pub fn parse(fix: &str) -> Result<Option<Rounding>, String> {
+        match fix.trim().to_lowercase().as_str() {
+            "" => Ok(None),
+            "round" => Ok(Some(Rounding::Round)),
+            "truncate" => Ok(Some(Rounding::Truncate)),
+            _ => Err(format!("Unknown auto-fix mode {}. Expected `round` or `truncate`", fix)),
+        }
+    }
+}
+
+/// A modification made by the auto-fix pass. The rows and columns are the ones of the original file.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CsvChange {
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    /// One of `trim`, `address-prefix`, `thousands-separator`, `round`, `truncate` and `drop-row`
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Why the row was dropped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// The outcome of the auto-fix pass: the cleaned CSV file, written in UTF-8 with commas, and the changelog of every
+/// modification
+#[derive(Serialize, Debug)]
+pub struct AutofixedCsv {
+    pub csv: String,
+    pub changes: Vec<CsvChange>,
+    /// Dialect of the original file
+    #[serde(skip)]
+    pub dialect: CsvDialect,
+}
+
+impl CsvChange {
+    fn edit(row: usize, column: usize, action: &str, before: &str, after: &str) -> CsvChange {
+        CsvChange {
+            row,
+            column: Some(column_name(column)),
+            action: action.to_string(),
+            before: Some(before.to_string()),
+            after: Some(after.to_string()),
+            reason: None,
+        }
+    }
+
+    fn drop_row(row: usize, reason: String) -> CsvChange {
+        CsvChange {
+            row,
+            column: None,
+            action: String::from("drop-row"),
+            before: None,
+            after: None,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// Reduce the decimals of an amount to the number of decimals of the token, rounding it half up or truncating it.
+/// Returns `None` when the amount has no excess decimals, or is not a decimal number.
+fn reduce_decimals(amount: &str, decimals: usize, rounding: Rounding) -> Option<String> {
+    let (whole, fraction) = amount.trim_start_matches('+').split_once('.')?;
+    let is_number = |digits: &str| digits.chars().all(|c| c.is_ascii_digit());
+    if fraction.len() <= decimals || !is_number(whole) || !is_number(fraction) {
+        return None;
+    }
+
+    let mut digits = format!("{}{}", whole, &fraction[..decimals]).into_bytes();
+    if rounding == Rounding::Round && fraction.as_bytes()[decimals] >= b'5' {
+        // Carry the rounding through the trailing nines
+        let mut position = digits.len();
+        while position > 0 && digits[position - 1] == b'9' {
+            position -= 1;
+            digits[position] = b'0';
+        }
+        match position {
+            0 => digits.insert(0, b'1'),
+            _ => digits[position - 1] += 1,
+        }
+    }
+
+    let digits = String::from_utf8(digits).unwrap();
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let whole = if whole.is_empty() { "0" } else { whole };
+    Some(if decimals == 0 { whole.to_string() } else { format!("{}.{}", whole, fraction) })
+}
+
+/// Clean up a CSV file before its validation: trim the cells, prefix the addresses with `0x`, strip the thousands
+/// separators of the amounts, reduce their excess decimals, then drop the rows which are still invalid or repeat the
+/// address of an earlier row. Returns `None` when the columns of the campaign cannot be located in the header, which
+/// the pass cannot repair.
+///
+/// A file delimited by semicolons usually comes from a locale writing decimal commas, where `1,500` stands for 1.5.
+/// Its commas are left untouched, so that such amounts are dropped as invalid instead of being read as thousands.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::{
+///     csv_autofix::{autofix_csv, Rounding},
+///     csv_campaign_parser::ParserOptions,
+/// };
+///
+/// let data = "address , amount\n9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,\"1,000.005\"\n0xthisIsNotAnAddress,1";
+/// let options = ParserOptions { decimals: Some(2), ..Default::default() };
+/// let fixed = autofix_csv(data.as_bytes(), &options, Rounding::Round).unwrap().unwrap();
+///
+/// assert_eq!(fixed.csv, "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1000.01\n");
+/// let actions: Vec<&str> = fixed.changes.iter().map(|change| change.action.as_str()).collect();
+/// assert_eq!(actions, vec!["trim", "trim", "address-prefix", "thousands-separator", "round", "drop-row"]);
+/// ```
+pub fn autofix_csv<R: Read>(
+    reader: R,
+    options: &ParserOptions,
+    rounding: Rounding,
+) -> Result<Option<AutofixedCsv>, ParserError> {
+    let (dialect, mut reader) = open_csv(reader)?;
+    let comma_thousands = dialect.delimiter != ';';
+    let mut changes = Vec::new();
+
+    let mut header = Vec::new();
+    for (column, cell) in reader.headers()?.iter().enumerate() {
+        if cell.trim() != cell {
+            changes.push(CsvChange::edit(1, column, "trim", cell, cell.trim()));
+        }
+        header.push(cell.trim().to_string());
+    }
+
+    let record_validator = RecordValidator::new(options);
+    let validators = record_validator.validators();
+    let columns = match map_csv_header(&StringRecord::from(header.clone()), &validators, &options.aliases) {
+        Ok(columns) => columns,
+        Err(_) => return Ok(None),
+    };
+    let (address_column, amount_column) = (columns[0], columns[1]);
+
+    let mut writer = WriterBuilder::new().terminator(csv::Terminator::Any(b'\n')).from_writer(Vec::new());
+    writer.write_record(&header)?;
+    // Row where each address was first found
+    let mut addresses: HashMap<String, usize> = HashMap::new();
+    let mut cells = StringRecord::new();
+    let mut record = StringRecord::new();
+
+    for row_index in 0.. {
+        let row = row_index + 2;
+        match reader.read_record(&mut cells) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(error) if matches!(error.kind(), csv::ErrorKind::Io(_)) => return Err(error.into()),
+            Err(_) => {
+                changes.push(CsvChange::drop_row(row, String::from("Invalid row")));
+                continue;
+            }
+        }
+        if columns.iter().any(|&column| column >= cells.len()) {
+            changes.push(CsvChange::drop_row(row, String::from("Insufficient columns")));
+            continue;
+        }
+
+        let mut row_changes = Vec::new();
+        let mut fixed: Vec<String> = Vec::with_capacity(cells.len());
+        for (column, cell) in cells.iter().enumerate() {
+            let mut value = cell.trim().to_string();
+            if value != cell {
+                row_changes.push(CsvChange::edit(row, column, "trim", cell, &value));
+            }
+
+            if column == address_column && UNPREFIXED_ADDRESS_REGEX.is_match(&value) {
+                let address = format!("0x{}", value.trim_start_matches("0X"));
+                row_changes.push(CsvChange::edit(row, column, "address-prefix", &value, &address));
+                value = address;
+            }
+            let thousands = THOUSANDS_REGEX.is_match(&value) && (comma_thousands || !value.contains(','));
+            if column == amount_column && thousands {
+                let amount: String = value.chars().filter(|c| !matches!(c, ',' | '_' | '\'' | ' ')).collect();
+                row_changes.push(CsvChange::edit(row, column, "thousands-separator", &value, &amount));
+                value = amount;
+            }
+            if let (true, Some(decimals)) = (column == amount_column, options.decimals) {
+                if let Some(amount) = reduce_decimals(&value, decimals, rounding) {
+                    let action = if rounding == Rounding::Round { "round" } else { "truncate" };
+                    row_changes.push(CsvChange::edit(row, column, action, &value, &amount));
+                    value = amount;
+                }
+            }
+            fixed.push(value);
+        }
+
+        record.clear();
+        columns.iter().for_each(|&column| record.push_field(&fixed[column]));
+        if let Err(errors) = record_validator.validate(&record, row_index, &validators) {
+            let reason = errors.into_iter().map(|error| error.message).collect::<Vec<_>>().join(". ");
+            changes.push(CsvChange::drop_row(row, reason));
+            continue;
+        }
+        let address = fixed[address_column].to_lowercase();
+        match addresses.get(&address) {
+            Some(first_row) if options.duplicates == Duplicates::Reject => {
+                let reason = format!("The address was already specified in row {}", first_row);
+                changes.push(CsvChange::drop_row(row, reason));
+                continue;
+            }
+            Some(_) => {}
+            None => {
+                addresses.insert(address, row);
+            }
+        }
+
+        changes.extend(row_changes);
+        writer.write_record(&fixed)?;
+    }
+
+    let csv = String::from_utf8(writer.into_inner().map_err(|error| error.to_string())?)?;
+    Ok(Some(AutofixedCsv { csv, changes, dialect }))
+}
+
+/// Run the auto-fix pass over a CSV file, then parse the cleaned file. The file is parsed as it is when its header
+/// cannot be repaired. The rows of the validation errors are the ones of the cleaned file, while the changelog
+/// refers to the rows of the original one.
+pub fn autofix_and_parse<R: Read>(
+    reader: R,
+    options: &ParserOptions,
+    rounding: Rounding,
+) -> Result<(CampaignCsvParsed, Option<AutofixedCsv>), ParserError> {
+    let mut data = Vec::new();
+    let mut reader = reader;
+    reader.read_to_end(&mut data)?;
+
+    match autofix_csv(&data[..], options, rounding)? {
+        Some(fixed) => {
+            let parsed = CampaignCsvParsed::parse(fixed.csv.as_bytes(), options)?;
+            Ok((CampaignCsvParsed { dialect: Some(fixed.dialect.clone()), ..parsed }, Some(fixed)))
+        }
+        None => Ok((CampaignCsvParsed::parse(&data[..], options)?, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduce_the_decimals() {
+        assert_eq!(reduce_decimals("1.005", 2, Rounding::Round).as_deref(), Some("1.01"));
+        assert_eq!(reduce_decimals("1.005", 2, Rounding::Truncate).as_deref(), Some("1.00"));
+        assert_eq!(reduce_decimals("9.996", 2, Rounding::Round).as_deref(), Some("10.00"));
+        assert_eq!(reduce_decimals("+0.5", 0, Rounding::Round).as_deref(), Some("1"));
+        assert_eq!(reduce_decimals(".994", 2, Rounding::Round).as_deref(), Some("0.99"));
+        assert_eq!(reduce_decimals("1.05", 2, Rounding::Round), None);
+        assert_eq!(reduce_decimals("100", 2, Rounding::Round), None);
+    }
+
+    #[test]
+    fn drop_the_repeated_addresses() {
+        let data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,2\n0x9AD7CAD4F10D0C3F875B8A2FD292590490C9F491,3";
+        let fixed = autofix_csv(data.as_bytes(), &Default::default(), Rounding::Round).unwrap().unwrap();
+
+        assert_eq!(fixed.csv.lines().count(), 3);
+        assert_eq!(
+            fixed.changes,
+            vec![CsvChange::drop_row(4, String::from("The address was already specified in row 2"))]
+        );
+
+        let options = ParserOptions { duplicates: Duplicates::Merge, ..Default::default() };
+        let fixed = autofix_csv(data.as_bytes(), &options, Rounding::Round).unwrap().unwrap();
+        assert_eq!(fixed.csv.lines().count(), 4);
+        assert!(fixed.changes.is_empty());
+    }
+
+    #[test]
+    fn parse_the_cleaned_file() {
+        let data = b"address;amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491;1 000.5\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc;2.999\n0xthisIsNotAnAddress;3";
+        let options = ParserOptions { decimals: Some(2), ..Default::default() };
+        let (parsed, fixed) = autofix_and_parse(&data[..], &options, Rounding::Truncate).unwrap();
+
+        assert!(parsed.validation_errors.is_empty());
+        assert_eq!(parsed.number_of_recipients, 2);
+        assert_eq!(parsed.total_amount, 100349.into());
+        assert_eq!(parsed.dialect.unwrap().delimiter, ';');
+        let fixed = fixed.unwrap();
+        assert_eq!(fixed.changes.len(), 3);
+        assert_eq!(fixed.changes[1].after.as_deref(), Some("2.99"));
+        assert_eq!(fixed.changes[2].reason.as_deref(), Some("Invalid Ethereum address"));
+    }
+
+    #[test]
+    fn keep_the_decimal_commas_of_semicolon_files() {
+        let data = "address;amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491;1,500\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc;1'500";
+        let options = ParserOptions { decimals: Some(2), ..Default::default() };
+        let fixed = autofix_csv(data.as_bytes(), &options, Rounding::Round).unwrap().unwrap();
+
+        assert_eq!(fixed.csv, "address,amount\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,1500\n");
+        assert_eq!(fixed.changes.len(), 2);
+        assert_eq!((fixed.changes[0].row, fixed.changes[0].action.as_str()), (2, "drop-row"));
+        assert_eq!(fixed.changes[1].action, "thousands-separator");
+    }
+
+    #[test]
+    fn keep_the_files_with_an_invalid_header() {
+        let data = "wallet_address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1";
+        assert!(autofix_csv(data.as_bytes(), &Default::default(), Rounding::Round).unwrap().is_none());
+    }
+}
@@ -1,19 +1,201 @@
-use csv::Reader;
+use calamine::{open_workbook_auto_from_rs, Data, Reader as _};
+use csv::{Reader, StringRecord};
+use ethers_core::types::U256;
 use ethers_rs::{Address, Eip55};
 use regex::Regex;
-use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, error::Error};
+use serde::{
+    de::{IgnoredAny, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt,
+    io::{BufRead, BufReader, Cursor, Read},
+};
 
-use crate::utils::csv_validator::{
-    validate_csv_header, validate_csv_row, AddressColumnValidator, AmountColumnValidator, ColumnValidator,
-    ValidationError,
+use crate::utils::{
+    campaign_lints::{amount_outliers, lint_address, lint_amount, Lint, ADDRESS_CASE_RULE},
+    campaign_rules::CampaignRules,
+    csv_dialect::{open_csv, CsvDialect},
+    csv_validator::{
+        column_name, column_position, leaf_column_validator, map_csv_header, validate_csv_row, AddressColumnValidator,
+        AmountColumnValidator, ChecksumAddressColumnValidator, ColumnAliases, ColumnValidator,
+        RawAmountColumnValidator, ValidationError,
+    },
+    leaf_schema::{LeafSchema, ADDRESS_FIELD, AMOUNT_FIELD, CLIFF_DURATION_FIELD, TOTAL_DURATION_FIELD},
 };
 
+type ParserError = Box<dyn Error + Send + Sync>;
+
 /// Record inside a CSV airstream campaign
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CampaignCsvRecord {
     pub address: String,
-    pub amount: u128,
+    pub amount: U256,
+    /// Values of the additional leaf columns, in the order of the leaf schema
+    #[serde(default)]
+    pub extra: Vec<String>,
+}
+
+/// Format of a recipient list
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum InputFormat {
+    /// A CSV file with a header
+    #[default]
+    Csv,
+    /// A JSON array of recipient objects
+    Json,
+    /// A stream of recipient objects, one JSON object per line
+    Ndjson,
+    /// An XLSX, XLS or ODS workbook, whose sheet is laid out like a CSV file
+    Spreadsheet,
+}
+
+impl InputFormat {
+    /// Get the format of the recipient lists sent with the specified content type, ignoring its parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::csv_campaign_parser::InputFormat;
+    ///
+    /// assert_eq!(InputFormat::from_content_type("application/json; charset=utf-8"), Some(InputFormat::Json));
+    /// assert_eq!(InputFormat::from_content_type("application/x-ndjson"), Some(InputFormat::Ndjson));
+    /// assert_eq!(InputFormat::from_content_type("multipart/form-data; boundary=x"), None);
+    /// ```
+    pub fn from_content_type(content_type: &str) -> Option<InputFormat> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        match mime.as_str() {
+            "text/csv" => Some(InputFormat::Csv),
+            "application/json" => Some(InputFormat::Json),
+            "application/x-ndjson" | "application/ndjson" | "application/jsonl" => Some(InputFormat::Ndjson),
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            | "application/vnd.oasis.opendocument.spreadsheet" => Some(InputFormat::Spreadsheet),
+            _ => None,
+        }
+    }
+
+    /// Get the format of an uploaded file, from the extension of its name or else from its content type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::csv_campaign_parser::InputFormat;
+    ///
+    /// assert_eq!(InputFormat::from_upload(Some("recipients.XLSX"), None), Some(InputFormat::Spreadsheet));
+    /// let csv = InputFormat::from_upload(Some("recipients.csv"), Some("application/vnd.ms-excel"));
+    /// assert_eq!(csv, Some(InputFormat::Csv));
+    /// let ods = InputFormat::from_upload(None, Some("application/vnd.oasis.opendocument.spreadsheet"));
+    /// assert_eq!(ods, Some(InputFormat::Spreadsheet));
+    /// assert_eq!(InputFormat::from_upload(Some("recipients"), None), None);
+    /// ```
+    pub fn from_upload(file_name: Option<&str>, content_type: Option<&str>) -> Option<InputFormat> {
+        let extension = file_name.and_then(|name| name.rsplit_once('.')).map(|(_, extension)| extension.to_lowercase());
+        let format = match extension.as_deref() {
+            Some("csv") => Some(InputFormat::Csv),
+            Some("json") => Some(InputFormat::Json),
+            Some("ndjson" | "jsonl") => Some(InputFormat::Ndjson),
+            Some("xlsx" | "xlsm" | "xls" | "ods") => Some(InputFormat::Spreadsheet),
+            _ => None,
+        };
+        format.or_else(|| content_type.and_then(InputFormat::from_content_type))
+    }
+}
+
+/// What to do with the recipients listed more than once
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Duplicates {
+    /// Report every repeated address as a validation error
+    #[default]
+    Reject,
+    /// Merge the rows of a repeated address into a single recipient, summing their amounts
+    Merge,
+}
+
+impl Duplicates {
+    /// Parse the duplicates mode provided through the query parameters, defaulting to rejecting them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::csv_campaign_parser::Duplicates;
+    ///
+    /// assert_eq!(Duplicates::parse(""), Ok(Duplicates::Reject));
+    /// assert_eq!(Duplicates::parse("Merge"), Ok(Duplicates::Merge));
+    /// assert!(Duplicates::parse("sum").is_err());
+    /// ```
+    pub fn parse(duplicates: &str) -> Result<Duplicates, String> {
+        match duplicates.trim().to_lowercase().as_str() {
+            "" | "reject" => Ok(Duplicates::Reject),
+            "merge" => Ok(Duplicates::Merge),
+            _ => Err(format!("Unknown duplicates mode {}. Expected `reject` or `merge`", duplicates)),
+        }
+    }
+}
+
+/// How the checksum of the mixed case addresses is enforced
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AddressChecksum {
+    /// Accept the addresses with a wrong checksum, which are only reported as warnings
+    #[default]
+    Lenient,
+    /// Report the addresses with a wrong checksum as validation errors
+    Strict,
+}
+
+impl AddressChecksum {
+    /// Parse the checksum mode provided through the query parameters, defaulting to the lenient mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::csv_campaign_parser::AddressChecksum;
+    ///
+    /// assert_eq!(AddressChecksum::parse(""), Ok(AddressChecksum::Lenient));
+    /// assert_eq!(AddressChecksum::parse("Strict"), Ok(AddressChecksum::Strict));
+    /// assert!(AddressChecksum::parse("eip55").is_err());
+    /// ```
+    pub fn parse(checksum: &str) -> Result<AddressChecksum, String> {
+        match checksum.trim().to_lowercase().as_str() {
+            "" | "lenient" => Ok(AddressChecksum::Lenient),
+            "strict" => Ok(AddressChecksum::Strict),
+            _ => Err(format!("Unknown checksum mode {}. Expected `lenient` or `strict`", checksum)),
+        }
+    }
+}
+
+/// A recipient listed in several rows, which were merged into a single recipient. The rows follow the numbering of the
+/// validation errors.
+#[derive(Clone, Debug, Serialize)]
+pub struct MergedRecipient {
+    pub address: String,
+    pub rows: Vec<usize>,
+}
+
+/// Options driving the parsing of a CSV airstream campaign
+#[derive(Clone, Debug, Default)]
+pub struct ParserOptions {
+    /// Format of the recipient list
+    pub format: InputFormat,
+    /// Number of decimals of the amounts. Without it, the amounts are raw integers expressed in base units.
+    pub decimals: Option<usize>,
+    /// Fields of the merkle tree leaves. Every field besides the index, the address and the amount is read from the
+    /// column with the same name.
+    pub leaf_schema: LeafSchema,
+    /// Report every validation error instead of stopping after the `max_errors` of the rules
+    pub all_errors: bool,
+    /// Name of the sheet holding the recipients of a spreadsheet. Defaults to the first sheet.
+    pub sheet: Option<String>,
+    /// Alternative names of the columns, which are located by name in the header
+    pub aliases: ColumnAliases,
+    /// What to do with the recipients listed more than once
+    pub duplicates: Duplicates,
+    /// Limits of the campaign
+    pub rules: CampaignRules,
+    /// How the checksum of the mixed case addresses is enforced
+    pub checksum: AddressChecksum,
 }
 
 /// The abstraction of a CSV airstream campaign
@@ -21,7 +203,13 @@ pub struct CampaignCsvParsed {
     pub records: Vec<CampaignCsvRecord>,
     pub validation_errors: Vec<ValidationError>,
     pub number_of_recipients: i32,
-    pub total_amount: u128,
+    pub total_amount: U256,
+    /// Dialect detected for the CSV files parsed with `parse`
+    pub dialect: Option<CsvDialect>,
+    /// Recipients whose rows were merged, when merging the duplicates
+    pub merged: Vec<MergedRecipient>,
+    /// Findings of the lint rules, which point at suspicious rows without making the file invalid
+    pub warnings: Vec<ValidationError>,
 }
 
 impl CampaignCsvParsed {
@@ -30,6 +218,9 @@ impl CampaignCsvParsed {
     /// `validation_errors` member. Keep in mind that this function uses the validators required for a valid
     /// Airstream campaign.
     ///
+    /// The rows are read one at a time into a reused buffer, so any `Read` source can be parsed without holding the
+    /// raw file in memory.
+    ///
     /// # Examples
     ///
     /// ```
@@ -41,115 +232,733 @@ impl CampaignCsvParsed {
     /// assert!(result.is_ok());
     /// let result = result.unwrap();
     /// assert_eq!(result.records.len(), 2);
-    /// assert_eq!(result.total_amount, 30000);
+    /// assert_eq!(result.total_amount, 30000.into());
     /// assert_eq!(result.number_of_recipients, 2);
     /// assert!(result.validation_errors.is_empty());
     /// ```
-    pub fn build(rdr: Reader<&[u8]>, decimals: usize) -> Result<CampaignCsvParsed, Box<dyn Error + Send + Sync>> {
+    pub fn build<R: Read>(rdr: Reader<R>, decimals: usize) -> Result<CampaignCsvParsed, Box<dyn Error + Send + Sync>> {
+        CampaignCsvParsed::build_with_options(rdr, &ParserOptions { decimals: Some(decimals), ..Default::default() })
+    }
+
+    /// Creates a `CampaignCsvParsed` from a reader holding a recipient list in the format set by the options. The JSON
+    /// and NDJSON lists go through the same validation as the CSV files, with one object per recipient whose members
+    /// are named after the columns. Spreadsheets are read whole, as their cells cannot be read as a stream, and their
+    /// sheet is validated like a CSV file starting at its first non-empty row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::csv_campaign_parser::{CampaignCsvParsed, InputFormat, ParserOptions};
+    ///
+    /// let data = r#"[
+    ///     {"address": "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", "amount": "100"},
+    ///     {"address": "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc", "amount": 200}
+    /// ]"#;
+    /// let options = ParserOptions { format: InputFormat::Json, ..Default::default() };
+    /// let result = CampaignCsvParsed::parse(data.as_bytes(), &options).unwrap();
+    /// assert!(result.validation_errors.is_empty());
+    /// assert_eq!(result.total_amount, 300.into());
+    /// ```
+    pub fn parse<R: Read>(reader: R, options: &ParserOptions) -> Result<CampaignCsvParsed, ParserError> {
+        match options.format {
+            InputFormat::Csv => {
+                let (dialect, rdr) = open_csv(reader)?;
+                let parsed = CampaignCsvParsed::build_with_options(rdr, options)?;
+                Ok(CampaignCsvParsed { dialect: Some(dialect), ..parsed })
+            }
+            InputFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+                let parsed = deserializer
+                    .deserialize_seq(JsonListVisitor { options })
+                    .and_then(|parsed| deserializer.end().map(|_| parsed));
+                match parsed {
+                    Ok(parsed) => parsed,
+                    Err(error) if error.is_io() => Err(error.into()),
+                    Err(error) => {
+                        let message = format!("Invalid JSON list of recipients: {}", error);
+                        Ok(CampaignCsvParsed::invalid(ValidationError { row: 0, message, ..Default::default() }))
+                    }
+                }
+            }
+            InputFormat::Ndjson => {
+                let items = BufReader::new(reader)
+                    .lines()
+                    .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+                    .map(|line| Ok(serde_json::from_str(&line?).map_err(|e| format!("Invalid JSON object: {}", e))));
+                CampaignCsvParsed::build_from_rows(&mut JsonRows::new(items, options), options)
+            }
+            InputFormat::Spreadsheet => {
+                let mut data = Vec::new();
+                let mut reader = reader;
+                reader.read_to_end(&mut data)?;
+                match SheetRows::open(data, options.sheet.as_deref()) {
+                    Ok(mut rows) => CampaignCsvParsed::build_from_rows(&mut rows, options),
+                    Err(error) => Ok(CampaignCsvParsed::invalid(error)),
+                }
+            }
+        }
+    }
+
+    /// Creates a `CampaignCsvParsed` from a reader, following the provided options. On top of the `address` and
+    /// `amount` columns, the additional columns required by the leaf schema are validated against their types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::{
+    ///     csv_campaign_parser::{CampaignCsvParsed, ParserOptions},
+    ///     utils::leaf_schema::LeafSchema,
+    /// };
+    /// use csv::ReaderBuilder;
+    ///
+    /// let csv_data = "address,amount,start_time\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,1700000000\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200,1700000100";
+    /// let options = ParserOptions {
+    ///     decimals: None,
+    ///     leaf_schema: LeafSchema::parse("index:uint256,address:address,amount:uint128,start_time:uint40").unwrap(),
+    ///     ..Default::default()
+    /// };
+    /// let result = CampaignCsvParsed::build_with_options(ReaderBuilder::new().from_reader(csv_data.as_bytes()), &options);
+    /// let result = result.unwrap();
+    /// assert!(result.validation_errors.is_empty());
+    /// assert_eq!(result.records[1].extra, vec!["1700000100"]);
+    /// ```
+    pub fn build_with_options<R: Read>(
+        rdr: Reader<R>,
+        options: &ParserOptions,
+    ) -> Result<CampaignCsvParsed, Box<dyn Error + Send + Sync>> {
         let mut rdr = rdr;
+        CampaignCsvParsed::build_from_rows(&mut rdr, options)
+    }
+
+    /// A parsed campaign holding a single validation error
+    fn invalid(error: ValidationError) -> CampaignCsvParsed {
+        CampaignCsvParsed {
+            records: Vec::new(),
+            validation_errors: vec![error],
+            number_of_recipients: 0,
+            total_amount: U256::zero(),
+            dialect: None,
+            merged: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Validate the rows of any source and collect the valid recipients.
+    fn build_from_rows(rows: &mut dyn RowSource, options: &ParserOptions) -> Result<CampaignCsvParsed, ParserError> {
         let mut validation_errors = Vec::new();
         let mut records: Vec<CampaignCsvRecord> = Vec::new();
-        let mut total_amount: u128 = 0;
+        let mut total_amount = U256::zero();
         let mut number_of_recipients: i32 = 0;
-        let pattern = format!(r"^[+]?\d*\.?\d{{0,{}}}$", decimals);
-        let amount_regex = Regex::new(&pattern).unwrap();
-
-        let amount_validator = AmountColumnValidator { regex: amount_regex };
-        let address_validator = AddressColumnValidator;
+        let record_validator = RecordValidator::new(options);
+        let validators = record_validator.validators();
+        // Position of each address inside the records, and row where it was first found
+        let mut unique_addresses: HashMap<String, usize> = HashMap::new();
+        let mut record_rows: Vec<usize> = Vec::new();
+        let mut first_spellings: Vec<String> = Vec::new();
+        let mut warnings: Vec<ValidationError> = Vec::new();
+        let mut merged: BTreeMap<usize, MergedRecipient> = BTreeMap::new();
+        let rules = &options.rules;
+        let limits = rules.amount_limits(options.decimals.unwrap_or_default())?;
 
-        let validators: Vec<&dyn ColumnValidator> = vec![&address_validator, &amount_validator];
-        let mut unique_addresses: HashSet<String> = HashSet::new();
-
-        // Validate the CSV header
-        let header = rdr.headers()?;
-        let header_errors = validate_csv_header(header, &validators);
-        if let Some(error) = header_errors {
-            validation_errors.push(error);
-            return Ok(CampaignCsvParsed { total_amount, number_of_recipients, records, validation_errors });
-        }
+        // Locate the columns inside the CSV header
+        let header = rows.header()?;
+        let columns = match map_csv_header(&header, &validators, &options.aliases) {
+            Ok(columns) => columns,
+            Err(mut error) => {
+                rows.locate(&mut error);
+                validation_errors.push(error);
+                return Ok(CampaignCsvParsed {
+                    total_amount,
+                    number_of_recipients,
+                    records,
+                    validation_errors,
+                    dialect: None,
+                    merged: Vec::new(),
+                    warnings: Vec::new(),
+                });
+            }
+        };
+        // The cell errors refer to the mapped columns, while the users need the columns of their file
+        let amount_error = |row: usize, message: String| ValidationError {
+            row,
+            message,
+            column: Some(column_name(columns[1])),
+            ..Default::default()
+        };
+        let warning = |row: usize, column: usize, lint: Lint| ValidationError {
+            row,
+            message: lint.message,
+            rule: Some(lint.rule.to_string()),
+            column: Some(column_name(columns[column])),
+            ..Default::default()
+        };
+        // Check the amount of a recipient and the total amount of the campaign against the rules
+        let check_amount = |amount: U256, total_amount: U256, row: usize| {
+            if limits.max_amount.is_some_and(|max_amount| amount > max_amount) {
+                let message = format!(
+                    "The amount is above the maximum of {} allowed per recipient",
+                    rules.max_amount.as_deref().unwrap_or_default()
+                );
+                return Some(amount_error(row, message));
+            }
+            if limits.max_total_amount.is_some_and(|max_total_amount| total_amount > max_total_amount) {
+                let message = format!(
+                    "The total amount of the campaign is above the maximum of {}",
+                    rules.max_total_amount.as_deref().unwrap_or_default()
+                );
+                return Some(amount_error(row, message));
+            }
+            None
+        };
+        let locate_column = |error: ValidationError| ValidationError {
+            column: error.column.as_deref().and_then(column_position).map(|position| column_name(columns[position])),
+            ..error
+        };
 
         let mut record_count = 0;
+        let mut cells = StringRecord::new();
+        let mut record = StringRecord::new();
+
+        for row_index in 0.. {
+            let result = rows.read_row(&mut cells)?;
+            if let Row::End = result {
+                break;
+            }
 
-        for (row_index, result) in rdr.records().enumerate() {
             record_count += 1;
             let row = row_index + 2;
-            if result.is_err() {
-                validation_errors.push(ValidationError { row, message: String::from("Invalid row") });
+            if !options.all_errors && rules.max_errors.is_some_and(|max_errors| validation_errors.len() >= max_errors) {
+                break;
+            }
+
+            if let Row::Invalid(message) = result {
+                validation_errors.push(ValidationError { row, message, ..Default::default() });
                 continue;
             }
-            let record = result.unwrap();
 
-            if validation_errors.len() >= 100 {
-                break;
+            if columns.iter().any(|&column| column >= cells.len()) {
+                let message = String::from("Insufficient columns");
+                validation_errors.push(ValidationError { row, message, ..Default::default() });
+                continue;
             }
+            record.clear();
+            columns.iter().for_each(|&column| record.push_field(&cells[column]));
 
             let address_field = record[0].trim();
             let amount_field = record[1].trim();
-            let row_errors = validate_csv_row(&record, row_index, &validators);
-            if !row_errors.is_empty() {
-                validation_errors.extend(row_errors);
-            }
+            let padded_amount = match record_validator.validate(&record, row_index, &validators) {
+                Ok(amount) => {
+                    warnings.extend(lint_address(address_field).map(|lint| warning(row, 0, lint)));
+                    warnings.extend(lint_amount(amount_field).map(|lint| warning(row, 1, lint)));
+                    Some(amount)
+                }
+                Err(row_errors) => {
+                    validation_errors.extend(row_errors.into_iter().map(locate_column));
+                    None
+                }
+            };
 
-            if unique_addresses.contains(&address_field.to_lowercase()) {
+            let duplicate = unique_addresses.get(&address_field.to_lowercase()).copied();
+            if let Some(position) = duplicate {
+                if first_spellings[position].trim_start_matches("0x") != address_field.trim_start_matches("0x") {
+                    let message =
+                        format!("This address differs only by case from the address of row {}", record_rows[position]);
+                    warnings.push(warning(row, 0, Lint { rule: ADDRESS_CASE_RULE, message }));
+                }
+            }
+            if duplicate.is_some() && options.duplicates == Duplicates::Reject {
                 validation_errors.push(ValidationError {
                     row,
                     message: String::from(
                         "Each recipient should have an unique address. This address was already specified in file",
                     ),
+                    ..Default::default()
                 });
             }
 
-            if validation_errors.is_empty() {
-                let address = address_field.to_string().to_lowercase();
-                let padded_amount = pad_value(amount_field, decimals);
-                total_amount += padded_amount;
+            if let (true, Some(padded_amount)) = (validation_errors.is_empty(), padded_amount) {
+                let sum = total_amount.checked_add(padded_amount);
+                if sum.is_none() {
+                    validation_errors.push(ValidationError {
+                        row,
+                        message: String::from("The total amount of the campaign does not fit into 256 bits"),
+                        ..Default::default()
+                    });
+                    continue;
+                }
+
+                let extra: Vec<String> =
+                    (2..validators.len()).map(|i| validators[i].leaf_value(record[i].trim())).collect();
+                if let Some(position) = duplicate {
+                    let recipient = &mut records[position];
+                    if recipient.extra != extra {
+                        validation_errors.push(ValidationError {
+                            row,
+                            message: format!(
+                                "This address was already specified in row {} with other leaf values. The rows cannot \
+                                 be merged",
+                                record_rows[position]
+                            ),
+                            ..Default::default()
+                        });
+                        continue;
+                    }
+
+                    // The amount of a recipient cannot overflow, as it is never more than the total amount
+                    if let Some(error) = check_amount(recipient.amount + padded_amount, sum.unwrap(), row) {
+                        validation_errors.push(error);
+                        continue;
+                    }
+                    total_amount = sum.unwrap();
+                    recipient.amount += padded_amount;
+                    let first_row = record_rows[position];
+                    merged
+                        .entry(position)
+                        .or_insert_with(|| MergedRecipient {
+                            address: recipient.address.clone(),
+                            rows: vec![first_row],
+                        })
+                        .rows
+                        .push(row);
+                    continue;
+                }
+
+                if let Some(error) = check_amount(padded_amount, sum.unwrap(), row) {
+                    validation_errors.push(error);
+                    continue;
+                }
+                if rules.max_recipients.is_some_and(|max_recipients| number_of_recipients as usize >= max_recipients) {
+                    let message = format!(
+                        "The campaign is above the maximum of {} recipients",
+                        rules.max_recipients.unwrap_or_default()
+                    );
+                    validation_errors.push(ValidationError { row, message, ..Default::default() });
+                    continue;
+                }
+
+                total_amount = sum.unwrap();
+                let address = address_field.to_lowercase();
                 number_of_recipients += 1;
-                unique_addresses.insert(address.clone());
+                unique_addresses.insert(address.clone(), records.len());
+                record_rows.push(row);
+                first_spellings.push(address_field.to_string());
                 records.push(CampaignCsvRecord {
                     address: Address::to_checksum_string(&Address::from_str(&address, false).unwrap()),
                     amount: padded_amount,
+                    extra,
                 });
             }
         }
 
-        if record_count <= 1 {
+        // The amounts of the merged rows are only known at the end
+        if let (true, Some(min_amount)) = (validation_errors.is_empty(), limits.min_amount) {
+            for (position, record) in records.iter().enumerate().filter(|(_, record)| record.amount < min_amount) {
+                let message = format!(
+                    "The amount of {} is below the minimum of {} allowed per recipient",
+                    record.address,
+                    rules.min_amount.as_deref().unwrap_or_default()
+                );
+                validation_errors.push(amount_error(record_rows[position], message));
+            }
+        }
+
+        // Merged rows can leave fewer recipients than rows
+        let min_recipients = rules.min_recipients;
+        if record_count < min_recipients
+            || (validation_errors.is_empty() && (number_of_recipients as usize) < min_recipients)
+        {
             let error = ValidationError {
                 row: 1,
-                message: String::from("An airstream campaign must have at least 2 recipients"),
+                message: format!("An airstream campaign must have at least {} recipients", min_recipients),
+                ..Default::default()
             };
             validation_errors.push(error);
         }
-        Ok(CampaignCsvParsed { total_amount, number_of_recipients, records, validation_errors })
+
+        // The outliers are found against the median amount, so only once every recipient is known
+        if validation_errors.is_empty() {
+            let amounts: Vec<U256> = records.iter().map(|record| record.amount).collect();
+            warnings.extend(
+                amount_outliers(&amounts).into_iter().map(|(position, lint)| warning(record_rows[position], 1, lint)),
+            );
+            warnings.sort_by_key(|warning| warning.row);
+        }
+
+        validation_errors.iter_mut().for_each(|error| rows.locate(error));
+        warnings.iter_mut().for_each(|warning| rows.locate(warning));
+        let merged = merged
+            .into_values()
+            .map(|recipient| MergedRecipient { rows: rows.locate_rows(&recipient.rows), ..recipient })
+            .collect();
+        Ok(CampaignCsvParsed {
+            total_amount,
+            number_of_recipients,
+            records,
+            validation_errors,
+            dialect: None,
+            merged,
+            warnings,
+        })
+    }
+}
+
+/// The largest integer that spreadsheets, storing numbers as 64-bit floats, can hold exactly
+const MAX_EXACT_NUMBER: f64 = 9_007_199_254_740_992.0;
+
+/// Rows of a spreadsheet. The header is the first non-empty row of the sheet, and the columns start at its first
+/// non-empty column. Empty rows are skipped.
+struct SheetRows {
+    sheet: String,
+    header: Vec<Data>,
+    header_row: usize,
+    first_column: usize,
+    rows: std::vec::IntoIter<(usize, Vec<Data>)>,
+    /// Sheet row of each row read so far, counted from 1 like spreadsheets do
+    row_numbers: Vec<usize>,
+}
+
+impl SheetRows {
+    /// Open the named sheet, or the first one, of a workbook
+    fn open(data: Vec<u8>, sheet: Option<&str>) -> Result<SheetRows, ValidationError> {
+        let invalid = |message: String| ValidationError { row: 0, message, ..Default::default() };
+        let mut workbook = open_workbook_auto_from_rs(Cursor::new(data))
+            .map_err(|e| invalid(format!("Invalid spreadsheet: {}", e)))?;
+
+        let name = match sheet.filter(|sheet| !sheet.is_empty()) {
+            Some(sheet) => sheet.to_string(),
+            None => {
+                workbook.sheet_names().first().cloned().ok_or_else(|| invalid("The spreadsheet has no sheet".into()))?
+            }
+        };
+        let range = workbook
+            .worksheet_range(&name)
+            .map_err(|_| invalid(format!("The spreadsheet has no sheet named `{}`", name)))?;
+
+        let (first_row, first_column) = range.start().unwrap_or_default();
+        let mut rows = range
+            .rows()
+            .enumerate()
+            .map(|(i, cells)| (first_row as usize + i + 1, cells.to_vec()))
+            .filter(|(_, cells)| cells.iter().any(|cell| !cell_value(cell).unwrap_or_default().trim().is_empty()));
+        let (header_row, header) = rows.next().unwrap_or((1, Vec::new()));
+
+        Ok(SheetRows {
+            sheet: name,
+            header,
+            header_row,
+            first_column: first_column as usize,
+            rows: rows.collect::<Vec<_>>().into_iter(),
+            row_numbers: Vec::new(),
+        })
+    }
+}
+
+/// Get the text of a spreadsheet cell. Dates are turned into Unix timestamps and durations into seconds, while the
+/// numbers too large to be stored exactly are rejected.
+fn cell_value(cell: &Data) -> Result<String, String> {
+    Ok(match cell {
+        Data::Empty => String::new(),
+        Data::String(value) | Data::DateTimeIso(value) | Data::DurationIso(value) => value.clone(),
+        Data::Int(value) => value.to_string(),
+        Data::Float(value) if value.abs() >= MAX_EXACT_NUMBER => {
+            return Err(String::from("The number is too large to be stored exactly in a spreadsheet, write it as text"))
+        }
+        Data::Float(value) => spreadsheet_number(*value),
+        Data::Bool(value) => value.to_string(),
+        Data::DateTime(value) if value.is_duration() => {
+            value.as_duration().map(|d| d.num_seconds().to_string()).unwrap_or_default()
+        }
+        Data::DateTime(value) => value.as_datetime().map(|d| d.and_utc().timestamp().to_string()).unwrap_or_default(),
+        Data::Error(error) => error.to_string(),
+    })
+}
+
+/// Write a number the way the spreadsheets display it. The fractions are rounded to 15 significant digits, which drops
+/// the noise left by the floating point arithmetic of the formulas, e.g. `=0.1*3` gives 0.3 and not
+/// 0.30000000000000004.
+fn spreadsheet_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        return value.to_string();
+    }
+    format!("{:.14e}", value).parse::<f64>().unwrap_or(value).to_string()
+}
+
+impl RowSource for SheetRows {
+    fn header(&mut self) -> Result<StringRecord, ParserError> {
+        Ok(self.header.iter().map(|cell| cell_value(cell).unwrap_or_default()).collect())
     }
+
+    fn read_row(&mut self, record: &mut StringRecord) -> Result<Row, ParserError> {
+        let (row_number, cells) = match self.rows.next() {
+            Some(row) => row,
+            None => return Ok(Row::End),
+        };
+        self.row_numbers.push(row_number);
+
+        record.clear();
+        for (position, cell) in cells.iter().enumerate() {
+            match cell_value(cell) {
+                Ok(value) => record.push_field(&value),
+                Err(message) => {
+                    let column = column_name(self.first_column + position);
+                    return Ok(Row::Invalid(format!("Column {}: {}", column, message)));
+                }
+            }
+        }
+        Ok(Row::Valid)
+    }
+
+    fn locate(&self, error: &mut ValidationError) {
+        error.sheet = Some(self.sheet.clone());
+        error.row = match error.row {
+            0 => 0,
+            1 => self.header_row,
+            row => self.row_numbers.get(row - 2).copied().unwrap_or(row),
+        };
+        let position = error.column.as_deref().and_then(column_position);
+        error.column = position.map(|position| column_name(self.first_column + position));
+    }
+}
+
+/// Outcome of reading a row out of a source
+enum Row {
+    Valid,
+    Invalid(String),
+    End,
+}
+
+/// A source of recipient rows, whose cells follow the columns of its header
+trait RowSource {
+    /// Read the header holding the names of the columns
+    fn header(&mut self) -> Result<StringRecord, ParserError>;
+
+    /// Read the next row into the record. A failure to read the source itself is returned as an error, while a
+    /// malformed row is reported as invalid.
+    fn read_row(&mut self, record: &mut StringRecord) -> Result<Row, ParserError>;
+
+    /// Adapt the position of a validation error, counted in CSV rows, to the layout of the source.
+    fn locate(&self, _error: &mut ValidationError) {}
+
+    /// Adapt CSV row numbers to the layout of the source, the way `locate` does.
+    fn locate_rows(&self, rows: &[usize]) -> Vec<usize> {
+        rows.iter()
+            .map(|&row| {
+                let mut error = ValidationError { row, ..Default::default() };
+                self.locate(&mut error);
+                error.row
+            })
+            .collect()
+    }
+}
+
+impl<R: Read> RowSource for Reader<R> {
+    fn header(&mut self) -> Result<StringRecord, ParserError> {
+        Ok(self.headers()?.clone())
+    }
+
+    fn read_row(&mut self, record: &mut StringRecord) -> Result<Row, ParserError> {
+        match self.read_record(record) {
+            Ok(true) => Ok(Row::Valid),
+            Ok(false) => Ok(Row::End),
+            Err(error) if matches!(error.kind(), csv::ErrorKind::Io(_)) => Err(error.into()),
+            Err(error) if matches!(error.kind(), csv::ErrorKind::Utf8 { .. }) => {
+                Ok(Row::Invalid(String::from("Invalid UTF-8 text")))
+            }
+            Err(_) => Ok(Row::Invalid(String::from("Invalid row"))),
+        }
+    }
+}
+
+/// Rows made out of JSON recipient objects, whose members are named after the columns. The items that cannot be read
+/// as JSON are provided as error messages.
+struct JsonRows<I> {
+    columns: Vec<String>,
+    items: I,
+}
+
+impl<I: Iterator<Item = Result<Result<Value, String>, std::io::Error>>> JsonRows<I> {
+    fn new(items: I, options: &ParserOptions) -> Self {
+        let mut columns = vec![ADDRESS_FIELD.to_string(), AMOUNT_FIELD.to_string()];
+        columns.extend(options.leaf_schema.extra_fields().iter().map(|field| field.name.clone()));
+        JsonRows { columns, items }
+    }
+}
+
+/// Visitor validating the recipients of a JSON list as they are read, so that the list is never held in memory. A
+/// malformed list fails as a whole, like when it is read at once.
+struct JsonListVisitor<'a> {
+    options: &'a ParserOptions,
+}
+
+impl<'de> Visitor<'de> for JsonListVisitor<'_> {
+    type Value = Result<CampaignCsvParsed, ParserError>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of recipients")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut error = None;
+        let items = std::iter::from_fn(|| match seq.next_element::<Value>() {
+            Ok(item) => item.map(|item| Ok(Ok(item))),
+            Err(e) => {
+                error = Some(e);
+                None
+            }
+        });
+        let parsed = CampaignCsvParsed::build_from_rows(&mut JsonRows::new(items, self.options), self.options);
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        // The parsing stops early once the rules allow no more errors, the rest of the list still has to be read
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(parsed)
+    }
+}
+
+impl<I: Iterator<Item = Result<Result<Value, String>, std::io::Error>>> RowSource for JsonRows<I> {
+    fn header(&mut self) -> Result<StringRecord, ParserError> {
+        Ok(StringRecord::from(self.columns.clone()))
+    }
+
+    fn read_row(&mut self, record: &mut StringRecord) -> Result<Row, ParserError> {
+        let item = match self.items.next() {
+            Some(item) => item?,
+            None => return Ok(Row::End),
+        };
+        let object = match item {
+            Ok(Value::Object(object)) => object,
+            Ok(_) => return Ok(Row::Invalid(String::from("Each recipient should be a JSON object"))),
+            Err(message) => return Ok(Row::Invalid(message)),
+        };
+
+        record.clear();
+        for column in &self.columns {
+            match object.get(column) {
+                Some(Value::String(value)) => record.push_field(value),
+                Some(Value::Number(value)) => record.push_field(&value.to_string()),
+                _ => return Ok(Row::Invalid(format!("The recipient should have a `{}` string or number", column))),
+            }
+        }
+        Ok(Row::Valid)
+    }
+
+    fn locate(&self, error: &mut ValidationError) {
+        let position = error.column.as_deref().and_then(column_position);
+        error.column = position.and_then(|position| self.columns.get(position).cloned());
+        if error.row >= 2 {
+            error.index = Some(error.row - 2);
+            error.row -= 1;
+        } else {
+            error.row = 0;
+        }
+    }
+}
+
+/// The validators of the columns of a campaign, laid out in the order of its records: the address, the amount, then the
+/// additional leaf columns
+pub(crate) struct RecordValidator {
+    validators: Vec<Box<dyn ColumnValidator>>,
+    schedule_columns: Option<(usize, usize)>,
+    decimals: usize,
 }
 
-/// Pad a number with the specified number of decimals
+impl RecordValidator {
+    pub(crate) fn new(options: &ParserOptions) -> RecordValidator {
+        let amount_validator: Box<dyn ColumnValidator> = match options.decimals {
+            Some(decimals) => {
+                let pattern = format!(r"^[+]?\d*\.?\d{{0,{}}}$", decimals);
+                Box::new(AmountColumnValidator { regex: Regex::new(&pattern).unwrap() })
+            }
+            None => Box::new(RawAmountColumnValidator),
+        };
+        let address_validator: Box<dyn ColumnValidator> = match options.checksum {
+            AddressChecksum::Lenient => Box::new(AddressColumnValidator),
+            AddressChecksum::Strict => Box::new(ChecksumAddressColumnValidator),
+        };
+
+        let extra_fields = options.leaf_schema.extra_fields();
+        let mut validators = vec![address_validator, amount_validator];
+        validators.extend(extra_fields.iter().map(|field| leaf_column_validator(field)));
+
+        // Once mapped, the additional leaf columns follow the `address` and `amount` columns
+        let column_of = |name: &str| extra_fields.iter().position(|f| f.name == name).map(|i| i + 2);
+        let schedule_columns = column_of(CLIFF_DURATION_FIELD).zip(column_of(TOTAL_DURATION_FIELD));
+        RecordValidator { validators, schedule_columns, decimals: options.decimals.unwrap_or_default() }
+    }
+
+    pub(crate) fn validators(&self) -> Vec<&dyn ColumnValidator> {
+        self.validators.iter().map(|v| v.as_ref()).collect()
+    }
+
+    /// Validate a record, given the validators borrowed out of this one, and get its amount in base units. The errors
+    /// found in a cell refer to the columns of the record.
+    pub(crate) fn validate(
+        &self,
+        record: &StringRecord,
+        row_index: usize,
+        validators: &[&dyn ColumnValidator],
+    ) -> Result<U256, Vec<ValidationError>> {
+        let row = row_index + 2;
+        let mut errors = validate_csv_row(record, row_index, validators);
+        if let (true, Some((cliff, total))) = (errors.is_empty(), self.schedule_columns) {
+            errors.extend(validate_schedule(record[cliff].trim(), record[total].trim(), row));
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        pad_value(record[1].trim(), self.decimals)
+            .map_err(|message| vec![ValidationError { row, message, ..Default::default() }])
+    }
+}
+
+/// Check that the cliff of a stream does not exceed its total duration. Both durations are expected to be valid.
+fn validate_schedule(cliff_duration: &str, total_duration: &str, row: usize) -> Option<ValidationError> {
+    let cliff_duration: u64 = cliff_duration.parse().ok()?;
+    let total_duration: u64 = total_duration.parse().ok()?;
+    if cliff_duration > total_duration {
+        return Some(ValidationError {
+            row,
+            message: String::from("The cliff duration cannot be greater than the total duration"),
+            ..Default::default()
+        });
+    }
+    None
+}
+
+/// Pad a number with the specified number of decimals, turning it into a 256 bits integer. Fails when the number has
+/// more decimals than requested or when the padded number does not fit into 256 bits.
 ///
 /// # Examples
 ///
 /// ```
+/// use ethers_core::types::U256;
 /// use sablier_merkle_api::csv_campaign_parser::pad_value;
 ///
-/// assert_eq!(pad_value("480.5", 3), 480500);
-/// assert_eq!(pad_value("613", 2), 61300);
-/// assert_eq!(pad_value("123.", 1), 1230);
+/// assert_eq!(pad_value("480.5", 3), Ok(U256::from(480500)));
+/// assert_eq!(pad_value("613", 2), Ok(U256::from(61300)));
+/// assert_eq!(pad_value("123.", 1), Ok(U256::from(1230)));
+/// assert!(pad_value("1.234", 2).is_err());
+/// assert!(pad_value("1000000000000000000000000000000000000000000000000000000000000000", 18).is_err());
 /// ```
-pub fn pad_value(s: &str, no_decimals: usize) -> u128 {
-    let decimal_point = s.find('.').unwrap_or(s.len());
-    if decimal_point == s.len() {
-        return format!("{}{}", s, "0".repeat(no_decimals)).parse().unwrap();
+pub fn pad_value(s: &str, no_decimals: usize) -> Result<U256, String> {
+    let value = s.trim().trim_start_matches('+');
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > no_decimals {
+        return Err(format!("The amount {} has more than {} decimals", s, no_decimals));
     }
 
-    let decimals = s.len() - decimal_point - 1;
-    let mut result = String::with_capacity(s.len() + no_decimals - decimals);
-    result.push_str(&s[0..decimal_point]);
-    result.push_str(&s[decimal_point + 1..]);
-
-    for _ in 0..(no_decimals - decimals) {
-        result.push('0');
+    let digits = format!("{}{}{}", whole, fraction, "0".repeat(no_decimals - fraction.len()));
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid amount {}", s));
     }
 
-    result.parse().unwrap()
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(U256::zero());
+    }
+    U256::from_dec_str(digits).map_err(|_| format!("The amount {} does not fit into 256 bits", s))
 }
 
 #[cfg(test)]
@@ -161,11 +970,55 @@ mod tests {
         ReaderBuilder::new().from_reader(input.as_bytes())
     }
 
+    /// Build a workbook with an empty `Notes` sheet and a `Recipients` sheet whose rows start at the `B2` cell
+    fn create_workbook(rows: &[(&str, f64)]) -> Vec<u8> {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        workbook.add_worksheet().set_name("Notes").unwrap();
+        let sheet = workbook.add_worksheet().set_name("Recipients").unwrap();
+        sheet.write_string(1, 1, "address").unwrap();
+        sheet.write_string(1, 2, "amount").unwrap();
+        for (i, (address, amount)) in rows.iter().enumerate() {
+            sheet.write_string(i as u32 + 2, 1, *address).unwrap();
+            sheet.write_number(i as u32 + 2, 2, *amount).unwrap();
+        }
+        workbook.save_to_buffer().unwrap()
+    }
+
     #[test]
     fn test_pad_value() {
-        assert_eq!(pad_value("480.5", 3), 480500);
-        assert_eq!(pad_value("613", 2), 61300);
-        assert_eq!(pad_value("123.", 1), 1230);
+        assert_eq!(pad_value("480.5", 3), Ok(U256::from(480500)));
+        assert_eq!(pad_value("613", 2), Ok(U256::from(61300)));
+        assert_eq!(pad_value("123.", 1), Ok(U256::from(1230)));
+        assert_eq!(pad_value("+0.5", 18), Ok(U256::exp10(17) * 5));
+        assert_eq!(pad_value("1", 77), Ok(U256::exp10(77)));
+    }
+
+    #[test]
+    fn test_pad_value_errors() {
+        assert!(pad_value("1.123", 2).is_err());
+        assert!(pad_value("1", 78).is_err());
+        assert!(pad_value("1e5", 2).is_err());
+    }
+
+    #[test]
+    fn test_csv_amount_overflow() {
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,1000000000000000000000000000000000000000000000000000000000000";
+        let result = CampaignCsvParsed::build(create_reader(csv_data), 18).unwrap();
+
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 3);
+        assert!(result.validation_errors[0].message.contains("does not fit into 256 bits"));
+
+        let max = U256::MAX.to_string();
+        let csv_data = format!(
+            "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,{}\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,1",
+            max
+        );
+        let result = CampaignCsvParsed::build(create_reader(&csv_data), 0).unwrap();
+
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 3);
+        assert_eq!(result.validation_errors[0].message, "The total amount of the campaign does not fit into 256 bits");
     }
 
     #[test]
@@ -177,9 +1030,427 @@ mod tests {
         let result = result.unwrap();
 
         assert_eq!(result.records.len(), 2);
-        assert_eq!(result.total_amount, 30000);
+        assert_eq!(result.total_amount, U256::from(30000));
+        assert_eq!(result.number_of_recipients, 2);
+        assert!(result.validation_errors.is_empty());
+    }
+
+    #[test]
+    fn test_valid_csv_from_any_reader() {
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
+        let reader = ReaderBuilder::new().buffer_capacity(8).from_reader(std::io::Cursor::new(csv_data.to_string()));
+        let result = CampaignCsvParsed::build(reader, 2).unwrap();
+
+        assert_eq!(result.records.len(), 2);
+        assert_eq!(result.total_amount, U256::from(30000));
+        assert!(result.validation_errors.is_empty());
+    }
+
+    #[test]
+    fn test_csv_with_raw_amounts() {
+        let options = ParserOptions::default();
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1000000000000000000000000\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,1";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.records[0].amount, U256::exp10(24));
+        assert_eq!(result.total_amount, U256::exp10(24) + 1);
+
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,+1";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert_eq!(result.validation_errors.len(), 2);
+        assert_eq!(result.validation_errors[0].row, 2);
+        assert_eq!(result.validation_errors[1].row, 3);
+    }
+
+    #[test]
+    fn test_json_list_errors_by_index() {
+        let options = ParserOptions { format: InputFormat::Json, decimals: Some(2), ..Default::default() };
+        let data = r#"[
+            {"address": "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", "amount": 100.5},
+            {"address": "0xThisIsNotAnAddress", "amount": "1"},
+            {"address": "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc"},
+            "0xf31b00e025584486f7c37Cf0AE0073c97c12c634"
+        ]"#;
+        let result = CampaignCsvParsed::parse(data.as_bytes(), &options).unwrap();
+
+        let positions: Vec<_> = result.validation_errors.iter().map(|e| (e.index, e.row)).collect();
+        assert_eq!(positions, vec![(Some(1), 2), (Some(2), 3), (Some(3), 4)]);
+        assert_eq!(result.validation_errors[1].message, "The recipient should have a `amount` string or number");
+        assert_eq!(result.total_amount, 10050.into());
+
+        let result = CampaignCsvParsed::parse(&b"[{\"address\": "[..], &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 0);
+        assert!(result.validation_errors[0].index.is_none());
+
+        for data in [&b"{\"address\": \"0x\"}"[..], b"[] []"] {
+            let result = CampaignCsvParsed::parse(data, &options).unwrap();
+            assert_eq!(result.validation_errors[0].row, 0);
+            assert!(result.validation_errors[0].message.starts_with("Invalid JSON list of recipients"));
+        }
+    }
+
+    #[test]
+    fn test_spreadsheet() {
+        let options = ParserOptions {
+            format: InputFormat::Spreadsheet,
+            decimals: Some(2),
+            sheet: Some(String::from("Recipients")),
+            ..Default::default()
+        };
+        let data = create_workbook(&[
+            ("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", 100.5),
+            ("0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc", 9007199254740991.0),
+        ]);
+        let result = CampaignCsvParsed::parse(&data[..], &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.total_amount, U256::from(900719925474099100u64 + 10050));
+
+        let data = create_workbook(&[
+            ("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", 100.123),
+            ("0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc", 1.2345678901234567e18),
+        ]);
+        let result = CampaignCsvParsed::parse(&data[..], &options).unwrap();
+
+        let errors = &result.validation_errors;
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].sheet.as_deref(), Some("Recipients"));
+        assert_eq!((errors[0].row, errors[0].column.as_deref()), (3, Some("C")));
+        assert_eq!((errors[1].row, errors[1].column.as_deref()), (4, None));
+        assert!(errors[1].message.starts_with("Column C: The number is too large"));
+    }
+
+    #[test]
+    fn test_spreadsheet_formula_results() {
+        let options = ParserOptions {
+            format: InputFormat::Spreadsheet,
+            decimals: Some(2),
+            sheet: Some(String::from("Recipients")),
+            ..Default::default()
+        };
+        let data = create_workbook(&[
+            ("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", 0.1 * 3.0),
+            ("0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc", 1.1 + 2.2),
+        ]);
+        let result = CampaignCsvParsed::parse(&data[..], &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.total_amount, 360.into());
+        assert_eq!(spreadsheet_number(2.0 / 3.0), "0.666666666666667");
+        assert_eq!(spreadsheet_number(9007199254740991.0), "9007199254740991");
+    }
+
+    #[test]
+    fn test_spreadsheet_sheets() {
+        let data = create_workbook(&[("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", 1.0)]);
+        let options = ParserOptions { format: InputFormat::Spreadsheet, ..Default::default() };
+        let result = CampaignCsvParsed::parse(&data[..], &options).unwrap();
+        assert_eq!(result.validation_errors[0].message, "Insufficient columns");
+        assert_eq!(result.validation_errors[0].sheet.as_deref(), Some("Notes"));
+
+        let options = ParserOptions { sheet: Some(String::from("Missing")), ..options };
+        let result = CampaignCsvParsed::parse(&data[..], &options).unwrap();
+        assert_eq!(result.validation_errors[0].row, 0);
+        assert_eq!(result.validation_errors[0].message, "The spreadsheet has no sheet named `Missing`");
+
+        let result = CampaignCsvParsed::parse(&b"address,amount"[..], &options).unwrap();
+        assert!(result.validation_errors[0].message.starts_with("Invalid spreadsheet"));
+    }
+
+    #[test]
+    fn test_ndjson_list() {
+        let options = ParserOptions { format: InputFormat::Ndjson, ..Default::default() };
+        let data = "{\"address\": \"0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\", \"amount\": \"100\"}\n\n{\"address\": \"0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc\", \"amount\": 200}\n";
+        let result = CampaignCsvParsed::parse(data.as_bytes(), &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.number_of_recipients, 2);
+        assert_eq!(result.total_amount, 300.into());
+
+        let data = "{\"address\": \"0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\", \"amount\": \"100\"}\n{not json}\n";
+        let result = CampaignCsvParsed::parse(data.as_bytes(), &options).unwrap();
+        assert_eq!(result.validation_errors[0].index, Some(1));
+        assert!(result.validation_errors[0].message.starts_with("Invalid JSON object"));
+    }
+
+    #[test]
+    fn test_csv_with_stream_columns() {
+        let options = ParserOptions {
+            decimals: None,
+            leaf_schema: LeafSchema::parse(
+                "uint256,address,uint128,start_time,cliff_duration,total_duration,tranche_percentages",
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let csv_data = "address,amount,start_time,cliff_duration,total_duration,tranche_percentages\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,1700000000,0,31536000,25;75\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200,0,86400,172800,100";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(
+            result.records[0].extra,
+            vec!["1700000000", "0", "31536000", "[250000000000000000,750000000000000000]"]
+        );
+        assert_eq!(result.records[1].extra, vec!["0", "86400", "172800", "[1000000000000000000]"]);
+
+        let csv_data = "address,amount,start_time,cliff_duration,total_duration,tranche_percentages\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,1700000000,86400,3600,100\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200,0,0,172800,50;40";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert_eq!(result.validation_errors.len(), 2);
+        assert_eq!(result.validation_errors[0].message, "The cliff duration cannot be greater than the total duration");
+        assert_eq!(result.validation_errors[0].row, 2);
+        assert_eq!(result.validation_errors[1].message, "The `tranche_percentages` percentages should add up to 100");
+        assert_eq!(result.validation_errors[1].row, 3);
+    }
+
+    #[test]
+    fn test_csv_columns_by_name() {
+        let options = ParserOptions {
+            decimals: Some(2),
+            leaf_schema: LeafSchema::parse("uint256,address,uint128,start_time").unwrap(),
+            aliases: ColumnAliases::parse("payout:amount").unwrap(),
+            ..Default::default()
+        };
+        let csv_data = "Name,Start_Time,Payout,Wallet\nAlice,1700000000,100.5,0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\nBob,1700000100,2,0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.records[1].address, "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc");
+        assert_eq!(result.records[1].amount, 200.into());
+        assert_eq!(result.records[1].extra, vec!["1700000100"]);
+
+        let csv_data = "name,start_time,payout,wallet\nAlice,1700000000,100.5,0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\nBob,1700000100,2.001,0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 3);
+        assert_eq!(result.validation_errors[0].column.as_deref(), Some("C"));
+    }
+
+    #[test]
+    fn test_csv_duplicated_columns() {
+        for header in ["address,amount,Address", "wallet,amount,account"] {
+            let csv_data = format!(
+                "{}\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1,0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491",
+                header
+            );
+            let result = CampaignCsvParsed::build(create_reader(&csv_data), 2).unwrap();
+
+            assert_eq!(result.validation_errors.len(), 1);
+            assert_eq!(result.validation_errors[0].row, 1);
+            assert_eq!(result.validation_errors[0].column.as_deref(), Some("C"));
+        }
+    }
+
+    #[test]
+    fn test_csv_aliases_only_replace_missing_columns() {
+        let csv_data = "address,amount,value,account\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1,7,me\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,2,8,you";
+        let result = CampaignCsvParsed::build(create_reader(csv_data), 2).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.total_amount, 300.into());
+
+        let options = ParserOptions {
+            decimals: Some(0),
+            leaf_schema: LeafSchema::parse("uint256,address,uint128,value:uint256").unwrap(),
+            ..Default::default()
+        };
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.records[1].extra, vec!["8"]);
+    }
+
+    #[test]
+    fn test_csv_merge_duplicates() {
+        let options = ParserOptions { decimals: Some(2), duplicates: Duplicates::Merge, ..Default::default() };
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0\n0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491,1.5\n0x9AD7CAD4F10D0C3F875B8A2FD292590490C9F491,0.5";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
         assert_eq!(result.number_of_recipients, 2);
+        assert_eq!(result.records[0].amount, 10200.into());
+        assert_eq!(result.total_amount, 30200.into());
+        assert_eq!(result.merged.len(), 1);
+        assert_eq!(result.merged[0].address, "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491");
+        assert_eq!(result.merged[0].rows, vec![2, 4, 5]);
+
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100.0\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,200.0";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors[0].message, "An airstream campaign must have at least 2 recipients");
+    }
+
+    #[test]
+    fn test_csv_merge_duplicates_with_other_leaf_values() {
+        let options = ParserOptions {
+            duplicates: Duplicates::Merge,
+            leaf_schema: LeafSchema::parse("uint256,address,uint128,start_time").unwrap(),
+            ..Default::default()
+        };
+        let csv_data = "address,amount,start_time\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,1700000000\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200,1700000000\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,1800000000";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 4);
+        assert_eq!(
+            result.validation_errors[0].message,
+            "This address was already specified in row 2 with other leaf values. The rows cannot be merged"
+        );
+    }
+
+    #[test]
+    fn test_csv_rules_amount_limits() {
+        let rules = CampaignRules {
+            min_amount: Some(String::from("1")),
+            max_amount: Some(String::from("250")),
+            max_total_amount: Some(String::from("400")),
+            ..Default::default()
+        };
+        let options = ParserOptions { decimals: Some(2), rules, ..Default::default() };
+
+        let csv_data = "amount,address\n300,0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\n200,0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 2);
+        assert_eq!(result.validation_errors[0].column.as_deref(), Some("A"));
+        assert_eq!(result.validation_errors[0].message, "The amount is above the maximum of 250 allowed per recipient");
+
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,250\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 3);
+        assert_eq!(result.validation_errors[0].message, "The total amount of the campaign is above the maximum of 400");
+
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,0.5\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 2);
+        assert_eq!(
+            result.validation_errors[0].message,
+            "The amount of 0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491 is below the minimum of 1 allowed per recipient"
+        );
+
+        let options = ParserOptions { duplicates: Duplicates::Merge, ..options };
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,0.5\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,0.5";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
         assert!(result.validation_errors.is_empty());
+        assert_eq!(result.records[0].amount, 100.into());
+    }
+
+    #[test]
+    fn test_csv_rules_recipients_limits() {
+        let rules = CampaignRules { min_recipients: 3, max_recipients: Some(3), ..Default::default() };
+        let options = ParserOptions { rules, ..Default::default() };
+
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].message, "An airstream campaign must have at least 3 recipients");
+
+        let csv_data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200\n0x3fe4c7b8d5ae3ce0f2d8e1d4c5a7a1c7f9f0ab12,300\n0x0000000000000000000000000000000000000001,400";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 5);
+        assert_eq!(result.validation_errors[0].message, "The campaign is above the maximum of 3 recipients");
+    }
+
+    #[test]
+    fn test_csv_rules_max_errors() {
+        let csv_data = format!("address,amount\n{}", "invalid,100\n".repeat(10));
+
+        let rules = CampaignRules { max_errors: Some(3), ..Default::default() };
+        let options = ParserOptions { rules, ..Default::default() };
+        let result = CampaignCsvParsed::build_with_options(create_reader(&csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 3);
+
+        let rules = CampaignRules { max_errors: None, ..Default::default() };
+        let options = ParserOptions { rules, ..Default::default() };
+        let result = CampaignCsvParsed::build_with_options(create_reader(&csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 10);
+    }
+
+    #[test]
+    fn test_rules_max_errors_counts_malformed_rows() {
+        let rules = CampaignRules { max_errors: Some(3), ..Default::default() };
+
+        let csv_data = format!("address,amount\n{}", "0x1,100,extra\n".repeat(10));
+        let options = ParserOptions { rules: rules.clone(), ..Default::default() };
+        let result = CampaignCsvParsed::build_with_options(create_reader(&csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 3);
+        assert!(result.validation_errors.iter().all(|error| error.message == "Invalid row"));
+
+        let json_data = format!("[{}1]", "1,".repeat(9));
+        let options = ParserOptions { rules, format: InputFormat::Json, ..Default::default() };
+        let result = CampaignCsvParsed::parse(json_data.as_bytes(), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 3);
+    }
+
+    #[test]
+    fn test_csv_lint_warnings() {
+        let options = ParserOptions { decimals: Some(18), duplicates: Duplicates::Merge, ..Default::default() };
+        let csv_data = "amount,address\n1,0x0000000000000000000000000000000000000000\n1,0xdead000000000000000042069420694206942069\n1,0x0000000000000000000000000000000000000001\n1,0x9aD7CAD4F10D0c3f875b8a2fd292590490c9f491\n0.30000000000000004,0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc\n1,0xF976AF93B0A5A9F55A7F285A3B5355B8575EB5BC";
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        let warnings: Vec<(usize, &str, &str)> = result
+            .warnings
+            .iter()
+            .map(|warning| (warning.row, warning.rule.as_deref().unwrap(), warning.column.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (2, "zero-address", "B"),
+                (3, "burn-address", "B"),
+                (4, "precompile-address", "B"),
+                (5, "checksum-mismatch", "B"),
+                (6, "excess-precision", "A"),
+                (7, "address-case", "B"),
+            ]
+        );
+        assert_eq!(result.warnings[5].message, "This address differs only by case from the address of row 6");
+    }
+
+    #[test]
+    fn test_csv_lint_amount_outliers() {
+        let rows: Vec<String> =
+            (1..=12).map(|i| format!("0x{:040x},{}", 0x1000 + i, if i == 5 { "150000" } else { "1000" })).collect();
+        let csv_data = format!("address,amount\n{}", rows.join("\n"));
+        let result = CampaignCsvParsed::build_with_options(create_reader(&csv_data), &Default::default()).unwrap();
+
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].row, 6);
+        assert_eq!(result.warnings[0].rule.as_deref(), Some("amount-outlier"));
+        assert_eq!(
+            result.warnings[0].message,
+            "The amount is more than 100 times above the median amount of the campaign"
+        );
+    }
+
+    #[test]
+    fn test_csv_strict_checksum() {
+        let csv_data = "amount,address\n1,0x9aD7CAD4F10D0c3f875b8a2fd292590490c9f491\n2,0xf976af93b0a5a9f55a7f285a3b5355b8575eb5bc";
+
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &Default::default()).unwrap();
+        assert!(result.validation_errors.is_empty());
+        assert_eq!(result.records[0].address, "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491");
+        assert_eq!(result.warnings[0].rule.as_deref(), Some("checksum-mismatch"));
+
+        let options = ParserOptions { checksum: AddressChecksum::Strict, ..Default::default() };
+        let result = CampaignCsvParsed::build_with_options(create_reader(csv_data), &options).unwrap();
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 2);
+        assert_eq!(result.validation_errors[0].column.as_deref(), Some("B"));
+        assert_eq!(
+            result.validation_errors[0].message,
+            "The address does not match its EIP-55 checksum and may hold a typo. Did you mean \
+             0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491?"
+        );
+        assert!(result.warnings.is_empty());
     }
 
     #[test]
@@ -222,6 +1493,23 @@ mod tests {
         assert_eq!(result.validation_errors[0].message, "Invalid row");
     }
 
+    #[test]
+    fn test_csv_invalid_utf_8_after_the_sniffed_prefix() {
+        let mut csv_data = String::from("address,amount,name\n");
+        for i in 0..2000 {
+            csv_data.push_str(&format!("0x{:040x},100,Zoe\n", i + 1));
+        }
+        let mut csv_data = csv_data.into_bytes();
+        csv_data.extend(b"0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,100,Zo\xEB\n");
+        let options = ParserOptions { decimals: Some(2), ..Default::default() };
+        let result = CampaignCsvParsed::parse(&csv_data[..], &options).unwrap();
+
+        assert_eq!(result.dialect.unwrap().encoding, "UTF-8");
+        assert_eq!(result.validation_errors.len(), 1);
+        assert_eq!(result.validation_errors[0].row, 2002);
+        assert_eq!(result.validation_errors[0].message, "Invalid UTF-8 text");
+    }
+
     #[test]
     fn test_csv_row_invalid_address() {
         let csv_data = "address,amount\n0xThisIsNotAnAddress,100.0\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,200.0";
@@ -0,0 +1,240 @@
+use csv::{StringRecord, WriterBuilder};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    io::Read,
+};
+
+use crate::{
+    csv_campaign_parser::{CampaignCsvParsed, ParserOptions},
+    utils::{csv_dialect::open_csv, csv_validator::ValidationError},
+};
+
+type ParserError = Box<dyn Error + Send + Sync>;
+
+/// Values which make two messages of the same error differ: the addresses and the row numbers
+static MESSAGE_VALUES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"0x[0-9a-fA-F]{40}|\brow \d+").unwrap());
+
+/// How the validation errors of an invalid file are reported
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ErrorReport {
+    /// List the first validation errors
+    #[default]
+    List,
+    /// Return the CSV file annotated with every validation error and warning of its rows
+    Annotated,
+}
+
+impl ErrorReport {
+    /// Parse the error report provided through the query parameters, defaulting to the list of errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::csv_report::ErrorReport;
+    ///
+    /// assert_eq!(ErrorReport::parse(""), Ok(ErrorReport::List));
+    /// assert_eq!(ErrorReport::parse("Annotated"), Ok(ErrorReport::Annotated));
+    /// assert!(ErrorReport::parse("pdf").is_err());
+    /// ```
+    pub fn parse(report: &str) -> Result<ErrorReport, String> {
+        match report.trim().to_lowercase().as_str() {
+            "" | "list" => Ok(ErrorReport::List),
+            "annotated" => Ok(ErrorReport::Annotated),
+            _ => Err(format!("Unknown error report {}. Expected `list` or `annotated`", report)),
+        }
+    }
+}
+
+/// Number of findings of the same type
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ReportCount {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub count: usize,
+}
+
+/// Summary of an annotated report. The errors are grouped by message, regardless of the addresses and rows they
+/// mention, and the warnings by lint rule.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ReportSummary {
+    pub rows: usize,
+    pub invalid_rows: usize,
+    pub errors: Vec<ReportCount>,
+    pub warnings: Vec<ReportCount>,
+}
+
+/// The original CSV file, written in UTF-8 with commas, with an `error` and a `warning` column appended to each row,
+/// along with its summary
+#[derive(Serialize, Debug)]
+pub struct ValidationReport {
+    pub csv: String,
+    pub summary: ReportSummary,
+}
+
+/// Get the type of an error out of its message, by masking the addresses and the rows it mentions.
+fn error_type(message: &str) -> String {
+    let mask = |value: &regex::Captures| if value[0].starts_with("0x") { "<address>" } else { "row <n>" };
+    MESSAGE_VALUES_REGEX.replace_all(message, mask).into_owned()
+}
+
+/// Count the findings by type, the most frequent first.
+fn count_by(findings: impl Iterator<Item = String>) -> Vec<ReportCount> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    findings.for_each(|kind| *counts.entry(kind).or_default() += 1);
+
+    let mut counts: Vec<ReportCount> = counts.into_iter().map(|(kind, count)| ReportCount { kind, count }).collect();
+    counts.sort_by_key(|count| std::cmp::Reverse(count.count));
+    counts
+}
+
+/// Group the messages of the findings by row.
+fn by_row(findings: &[ValidationError]) -> HashMap<usize, Vec<&str>> {
+    let mut rows: HashMap<usize, Vec<&str>> = HashMap::new();
+    findings.iter().for_each(|finding| rows.entry(finding.row).or_default().push(&finding.message));
+    rows
+}
+
+/// Annotate a CSV file with the validation errors and the warnings found by the parser in each of its rows. The
+/// errors which do not belong to a row, like the ones about the header or the campaign as a whole, only appear in the
+/// summary.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::{
+///     csv_campaign_parser::{CampaignCsvParsed, ParserOptions},
+///     csv_report::annotate_csv,
+/// };
+///
+/// let data = "address,amount\n0xthisIsNotAnAddress,1\n0x000000000000000000000000000000000000dEaD,2";
+/// let options = ParserOptions { all_errors: true, ..Default::default() };
+/// let parsed = CampaignCsvParsed::parse(data.as_bytes(), &options).unwrap();
+/// let report = annotate_csv(data.as_bytes(), &parsed).unwrap();
+///
+/// let mut lines = report.csv.lines();
+/// assert_eq!(lines.next(), Some("address,amount,error,warning"));
+/// assert_eq!(lines.next(), Some("0xthisIsNotAnAddress,1,Invalid Ethereum address,"));
+/// assert_eq!(
+///     lines.next(),
+///     Some("0x000000000000000000000000000000000000dEaD,2,,This address is commonly used to burn tokens")
+/// );
+/// assert_eq!((report.summary.rows, report.summary.invalid_rows), (2, 1));
+/// ```
+pub fn annotate_csv<R: Read>(reader: R, parsed: &CampaignCsvParsed) -> Result<ValidationReport, ParserError> {
+    let (_, mut reader) = open_csv(reader)?;
+    let errors = by_row(&parsed.validation_errors);
+    let warnings = by_row(&parsed.warnings);
+
+    let mut header = reader.headers()?.clone();
+    let columns = header.len();
+    header.push_field("error");
+    header.push_field("warning");
+    let mut writer =
+        WriterBuilder::new().flexible(true).terminator(csv::Terminator::Any(b'\n')).from_writer(Vec::new());
+    writer.write_record(&header)?;
+
+    let mut cells = StringRecord::new();
+    let mut rows = 0;
+    for row in 2.. {
+        // The rows which do not have as many cells as the header are read all the same
+        match reader.read_record(&mut cells) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(error) if matches!(error.kind(), csv::ErrorKind::Io(_)) => return Err(error.into()),
+            Err(_) => {}
+        }
+
+        rows += 1;
+        let mut record: Vec<&str> = cells.iter().collect();
+        record.resize(record.len().max(columns), "");
+        let joined =
+            |findings: &HashMap<usize, Vec<&str>>| findings.get(&row).map(|f| f.join(". ")).unwrap_or_default();
+        let (error, warning) = (joined(&errors), joined(&warnings));
+        record.push(&error);
+        record.push(&warning);
+        writer.write_record(&record)?;
+    }
+
+    let summary = ReportSummary {
+        rows,
+        invalid_rows: errors.keys().filter(|&&row| row >= 2).count(),
+        errors: count_by(parsed.validation_errors.iter().map(|error| error_type(&error.message))),
+        warnings: count_by(parsed.warnings.iter().map(|warning| warning.rule.clone().unwrap_or_default())),
+    };
+    let csv = String::from_utf8(writer.into_inner().map_err(|error| error.to_string())?)?;
+    Ok(ValidationReport { csv, summary })
+}
+
+/// Parse a CSV file and annotate it with its validation errors when it is invalid. Every error is reported, regardless
+/// of the `max_errors` of the rules, and the file is held in memory as it is read twice.
+pub fn parse_with_report<R: Read>(
+    reader: R,
+    options: &ParserOptions,
+) -> Result<(CampaignCsvParsed, Option<ValidationReport>), ParserError> {
+    let mut data = Vec::new();
+    let mut reader = reader;
+    reader.read_to_end(&mut data)?;
+
+    let options = ParserOptions { all_errors: true, ..options.clone() };
+    let parsed = CampaignCsvParsed::parse(&data[..], &options)?;
+    if parsed.validation_errors.is_empty() {
+        return Ok((parsed, None));
+    }
+    let report = annotate_csv(&data[..], &parsed)?;
+    Ok((parsed, Some(report)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_the_errors_by_type() {
+        let data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,2\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,0\n0xf31b00e025584486f7c37Cf0AE0073c97c12c634,0\n0x0000000000000000000000000000000000000001\n";
+        let (parsed, report) = parse_with_report(data.as_bytes(), &Default::default()).unwrap();
+        let report = report.unwrap();
+
+        assert_eq!(parsed.validation_errors.len(), 4);
+        assert_eq!(report.summary.rows, 5);
+        assert_eq!(report.summary.invalid_rows, 4);
+        assert_eq!(
+            report.summary.errors,
+            vec![
+                ReportCount { kind: String::from("The amount cannot be 0"), count: 2 },
+                ReportCount {
+                    kind: String::from(
+                        "Each recipient should have an unique address. This address was already specified in file"
+                    ),
+                    count: 1
+                },
+                ReportCount { kind: String::from("Invalid row"), count: 1 },
+            ]
+        );
+        assert_eq!(report.csv.lines().nth(5), Some("0x0000000000000000000000000000000000000001,,Invalid row,"));
+    }
+
+    #[test]
+    fn mask_the_values_of_the_messages() {
+        let message = "This address was already specified in row 12 with other leaf values. The rows cannot be merged";
+        assert_eq!(
+            error_type(message),
+            "This address was already specified in row <n> with other leaf values. The rows cannot be merged"
+        );
+        let message =
+            "The amount of 0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491 is below the minimum of 1 allowed per recipient";
+        assert_eq!(error_type(message), "The amount of <address> is below the minimum of 1 allowed per recipient");
+    }
+
+    #[test]
+    fn skip_the_report_of_valid_files() {
+        let data = "address,amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,1\n0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc,2";
+        let (parsed, report) = parse_with_report(data.as_bytes(), &Default::default()).unwrap();
+
+        assert!(parsed.validation_errors.is_empty());
+        assert!(report.is_none());
+    }
+}
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::utils::{leaf_schema::LeafSchema, merkle::MerkleTreeDump};
 
 /// Struct that represents the abstraction of an airstream campaign recipient
 #[derive(Deserialize, Serialize, Debug)]
@@ -7,12 +10,133 @@ pub struct RecipientDto {
     pub amount: String,
 }
 
-/// Struct that represents the abstraction of an airstream campaign
+/// Version of the campaign file schema written by this service
+pub const CAMPAIGN_SCHEMA_VERSION: u32 = 2;
+
+/// Struct that represents the abstraction of an airstream campaign, following the latest version of the campaign file
+/// schema
 #[derive(Deserialize, Serialize, Debug)]
 pub struct PersistentCampaignDto {
+    pub version: u32,
+    pub total_amount: String,
+    pub number_of_recipients: i32,
+    pub root: String,
+    pub merkle_tree: MerkleTreeDump,
+    /// Fields of the merkle tree leaves. Missing from the campaigns created before it was introduced, which all use the
+    /// default `[index, address, amount]` leaf.
+    #[serde(default)]
+    pub leaf_schema: LeafSchema,
+    pub recipients: Vec<RecipientDto>,
+    /// Lowercase recipient address to recipient index map. Missing from the campaigns created before it was introduced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_index: Option<HashMap<String, usize>>,
+}
+
+/// Struct that represents the abstraction of an airstream campaign as stored before the schema was versioned, with the
+/// merkle tree dump encoded as a JSON string
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PersistentCampaignV1Dto {
     pub total_amount: String,
     pub number_of_recipients: i32,
     pub root: String,
     pub merkle_tree: String,
     pub recipients: Vec<RecipientDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_index: Option<HashMap<String, usize>>,
+}
+
+impl From<PersistentCampaignV1Dto> for PersistentCampaignDto {
+    /// Upgrade a version 1 campaign. An embedded merkle tree that cannot be decoded is replaced by an empty dump, so
+    /// that the campaign summary stays readable while any use of the tree reports an invalid campaign.
+    fn from(campaign: PersistentCampaignV1Dto) -> Self {
+        PersistentCampaignDto {
+            version: CAMPAIGN_SCHEMA_VERSION,
+            total_amount: campaign.total_amount,
+            number_of_recipients: campaign.number_of_recipients,
+            root: campaign.root,
+            merkle_tree: serde_json::from_str(&campaign.merkle_tree).unwrap_or_default(),
+            leaf_schema: LeafSchema::default(),
+            recipients: campaign.recipients,
+            address_index: campaign.address_index,
+        }
+    }
+}
+
+/// Struct that represents the manifest of a sharded airstream campaign. The recipients are split into shards keyed by
+/// the first `prefix_length` hex characters of their lowercase address, and `shards` maps every key to the CID of its
+/// shard.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ShardedCampaignDto {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub layout: String,
+    pub total_amount: String,
+    pub number_of_recipients: i32,
+    pub root: String,
+    pub prefix_length: usize,
+    #[serde(default)]
+    pub leaf_schema: LeafSchema,
+    pub shards: BTreeMap<String, String>,
+}
+
+/// Struct that represents a recipient of a sharded airstream campaign, together with its precomputed proof
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ShardRecipientDto {
+    pub index: usize,
+    pub address: String,
+    pub amount: String,
+    pub proof: Vec<String>,
+    /// Values of the leaf of the recipient, following the leaf schema of the campaign
+    #[serde(default)]
+    pub leaf: Vec<String>,
+}
+
+/// Struct that represents a shard of a sharded airstream campaign. The shards written before it was versioned follow
+/// the schema version of their manifest.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CampaignShardDto {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub root: String,
+    pub recipients: Vec<ShardRecipientDto>,
+}
+
+/// Struct that represents the body of a batch eligibility request
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BatchEligibilityDto {
+    #[serde(default)]
+    pub cid: String,
+    pub addresses: Vec<String>,
+}
+
+/// Struct that represents the body of a multiproof request. The leaves can be selected both by recipient address and
+/// by index.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MultiProofDto {
+    pub cid: String,
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub indexes: Vec<usize>,
+}
+
+/// Struct that represents the body of a proof verification request. The expected root is either provided directly or
+/// read from the campaign stored under the CID. The leaf follows the schema of the campaign when the CID is provided,
+/// and `leaf_encoding` otherwise, with the values of the additional leaf fields taken from `fields`.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct VerifyDto {
+    pub root: Option<String>,
+    pub cid: Option<String>,
+    pub index: usize,
+    pub address: String,
+    pub amount: String,
+    pub proof: Vec<String>,
+    #[serde(default)]
+    pub leaf_encoding: String,
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+}
+
+fn default_version() -> u32 {
+    CAMPAIGN_SCHEMA_VERSION
 }
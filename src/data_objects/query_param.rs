@@ -10,15 +10,65 @@ pub struct Eligibility {
     pub cid: String,
 }
 
+/// Query parameters for batch eligibility endpoint
+#[derive(Deserialize)]
+pub struct BatchEligibility {
+    #[serde(default = "default_string")]
+    pub cid: String,
+}
+
 fn default_string() -> String {
     "".to_string()
 }
 
 /// Query parameters for create endpoint
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct Create {
+    /// Number of decimals of the token, used to scale the human readable amounts of the recipients. When it is omitted
+    /// or set to `raw`, the amounts are read as integers already expressed in base units: a list of human readable
+    /// amounts sent without `decimals` is rejected if it holds fractions, and is otherwise taken as base units.
     #[serde(default = "default_string")]
     pub decimals: String,
+
+    #[serde(default = "default_string")]
+    pub layout: String,
+
+    /// Comma separated leaf schema, e.g. `uint256,address,uint128`. Defaults to `[index, address, amount]` leaves.
+    #[serde(default = "default_string")]
+    pub leaf_encoding: String,
+
+    /// Name of the sheet holding the recipients, for spreadsheet uploads. Defaults to the first sheet.
+    #[serde(default = "default_string")]
+    pub sheet: String,
+
+    /// Comma separated column aliases, e.g. `payout:amount,to:address`, added to the default ones.
+    #[serde(default = "default_string")]
+    pub aliases: String,
+
+    /// Set to `merge` to merge the rows of the recipients listed more than once. Defaults to rejecting them.
+    #[serde(default = "default_string")]
+    pub duplicates: String,
+
+    /// Name of the campaign rules profile, out of the `CAMPAIGN_RULES` configuration. Defaults to the `default`
+    /// profile.
+    #[serde(default = "default_string")]
+    pub rules: String,
+
+    /// Set to `strict` to reject the mixed case addresses which do not match their EIP-55 checksum. Defaults to
+    /// reporting them as warnings.
+    #[serde(default = "default_string")]
+    pub checksum: String,
+
+    /// Set to `round` or `truncate` to clean up a CSV file before its preview, with the excess decimals of its amounts
+    /// rounded or truncated. Only used by the create preview endpoint.
+    #[serde(default = "default_string")]
+    pub fix: String,
+
+    /// Set to `annotated` to download an invalid CSV file with the errors and warnings of each row, their summary being
+    /// sent in the `X-Message` header, instead of getting the list of the first errors. Only used by the create
+    /// endpoint, and only available for CSV files.
+    #[serde(default = "default_string")]
+    pub report: String,
 }
 
 /// Query parameters for validity endpoint
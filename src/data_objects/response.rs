@@ -1,8 +1,16 @@
-use crate::utils::csv_validator::ValidationError;
+use crate::{
+    csv_autofix::AutofixedCsv,
+    csv_campaign_parser::MergedRecipient,
+    csv_report::ReportSummary,
+    utils::{csv_dialect::CsvDialect, csv_validator::ValidationError},
+};
 use serde::Serialize;
 use serde_json::Value as Json;
 use vercel_runtime as Vercel;
-use warp::reply::WithStatus;
+use warp::Reply;
+
+/// Header carrying the JSON message of the responses which send an attachment as their body
+pub const MESSAGE_HEADER: &str = "X-Message";
 
 /// Generic Error Response structure
 #[derive(Serialize, Debug)]
@@ -10,14 +18,30 @@ pub struct GeneralErrorResponse {
     pub message: String,
 }
 
-/// Struct for the response of the create endpoint when the provided csv is invalid
+/// Struct for the response of the create endpoint when the provided csv is invalid. The `dialect` detected for CSV
+/// files is reported along with the errors, as a wrong guess is often what makes the file invalid.
 #[derive(Serialize, Debug)]
 pub struct ValidationErrorResponse {
     pub status: String,
     pub errors: Vec<ValidationError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<CsvDialect>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ValidationError>,
+}
+
+/// Struct for the response of the create endpoint when the provided csv is invalid and an annotated report was
+/// requested in place of the list of errors. The annotated file itself is sent as the attachment of the response.
+#[derive(Serialize, Debug)]
+pub struct ValidationReportResponse {
+    pub status: String,
+    pub summary: ReportSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<CsvDialect>,
 }
 
-/// Struct for the success response of the create endpoint
+/// Struct for the success response of the create endpoint. The `warnings` point at suspicious rows which did not
+/// prevent the upload.
 #[derive(Serialize, Debug)]
 pub struct UploadSuccessResponse {
     pub status: String,
@@ -25,6 +49,39 @@ pub struct UploadSuccessResponse {
     pub total: String,
     pub recipients: String,
     pub cid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<CsvDialect>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub merged: Vec<MergedRecipient>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ValidationError>,
+}
+
+/// Struct for the response of the create preview endpoint. The root, the totals and the depth of the merkle tree are
+/// only available when the csv file is valid, while `errors` lists every validation error found in the file and
+/// `warnings` the findings of the lint rules. The cleaned file and its changelog are returned as `fixed` when the
+/// auto-fix pass was requested.
+#[derive(Serialize, Debug)]
+pub struct PreviewResponse {
+    pub status: String,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipients: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+    pub errors: Vec<ValidationError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<CsvDialect>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub merged: Vec<MergedRecipient>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ValidationError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<AutofixedCsv>,
 }
 
 /// Struct for the success response of the eligibility endpoint
@@ -34,6 +91,45 @@ pub struct EligibilityResponse {
     pub proof: Vec<String>,
     pub address: String,
     pub amount: String,
+    /// Values of the leaf of the recipient, in the order expected by the campaign contract
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub leaf: Vec<String>,
+}
+
+/// Struct for the eligibility of a single address inside the response of the batch eligibility endpoint. The
+/// `eligibility` member is empty when the address is not eligible for the campaign.
+#[derive(Serialize, Debug)]
+pub struct BatchEligibilityEntry {
+    pub address: String,
+    pub eligible: bool,
+    pub eligibility: Option<EligibilityResponse>,
+}
+
+/// Struct for the success response of the batch eligibility endpoint
+#[derive(Serialize, Debug)]
+pub struct BatchEligibilityResponse {
+    pub cid: String,
+    pub results: Vec<BatchEligibilityEntry>,
+}
+
+/// Struct for the success response of the multiproof endpoint. The members follow the arguments of OpenZeppelin
+/// `MerkleProof.multiProofVerify`, with the leaves provided as their unhashed values.
+#[derive(Serialize, Debug)]
+pub struct MultiProofResponse {
+    pub root: String,
+    pub leaves: Vec<Vec<String>>,
+    pub proof: Vec<String>,
+    #[serde(rename = "proofFlags")]
+    pub proof_flags: Vec<bool>,
+}
+
+/// Struct for the success response of the verify endpoint
+#[derive(Serialize, Debug)]
+pub struct VerifyResponse {
+    pub valid: bool,
+    pub leaf: String,
+    pub computed_root: String,
+    pub expected_root: String,
 }
 
 /// Struct for the success response of the validity endpoint
@@ -45,48 +141,111 @@ pub struct ValidResponse {
     pub cid: String,
 }
 
-/// Generic API response
+/// A file sent as a download in place of the JSON message, for instance a CSV file to open in a spreadsheet
+#[derive(Serialize, Debug)]
+pub struct Attachment {
+    pub file_name: String,
+    pub content_type: String,
+    pub content: String,
+}
+
+/// Generic API response. When it holds an attachment, the attachment is sent as the body and the message is moved to
+/// the `X-Message` header.
 #[derive(Serialize, Debug)]
 pub struct R {
     pub status: u16,
     pub message: Json,
+    #[serde(skip)]
+    pub attachment: Option<Attachment>,
 }
 
 /// Create a Bad Request type of response
 pub fn bad_request(json_response: Json) -> R {
-    R { status: warp::http::StatusCode::BAD_REQUEST.as_u16(), message: json_response }
+    R { status: warp::http::StatusCode::BAD_REQUEST.as_u16(), message: json_response, attachment: None }
 }
 
 /// Create a UNAUTHORIZED type of response
 pub fn unauthorized(json_response: Json) -> R {
-    R { status: warp::http::StatusCode::UNAUTHORIZED.as_u16(), message: json_response }
+    R { status: warp::http::StatusCode::UNAUTHORIZED.as_u16(), message: json_response, attachment: None }
 }
 
 /// Create an Ok type of response
 pub fn ok(json_response: Json) -> R {
-    R { status: warp::http::StatusCode::OK.as_u16(), message: json_response }
+    R { status: warp::http::StatusCode::OK.as_u16(), message: json_response, attachment: None }
 }
 
 /// Create an Internal Server Error type of response
 pub fn internal_server_error(json_response: Json) -> R {
-    R { status: warp::http::StatusCode::INTERNAL_SERVER_ERROR.as_u16(), message: json_response }
+    R { status: warp::http::StatusCode::INTERNAL_SERVER_ERROR.as_u16(), message: json_response, attachment: None }
+}
+
+/// Send a file as the body of the response, keeping its message in the `X-Message` header
+pub fn with_attachment(response: R, attachment: Attachment) -> R {
+    R { attachment: Some(attachment), ..response }
+}
+
+/// Headers describing the attachment of a response, and the message moved out of its body
+fn attachment_headers(message: &Json, attachment: &Attachment) -> [(&'static str, String); 3] {
+    [
+        ("content-type", attachment.content_type.clone()),
+        ("content-disposition", format!("attachment; filename=\"{}\"", attachment.file_name)),
+        (MESSAGE_HEADER, header_json(message)),
+    ]
+}
+
+/// Serialize a message for a header, which only holds ASCII characters, escaping the other characters the way JSON
+/// does.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::data_objects::response::header_json;
+///
+/// assert_eq!(header_json(&serde_json::json!({ "column": "Bénéficiaire" })), r#"{"column":"B\u00e9n\u00e9ficiaire"}"#);
+/// ```
+pub fn header_json(message: &Json) -> String {
+    let mut header = String::new();
+    for c in message.to_string().chars() {
+        if c.is_ascii() {
+            header.push(c);
+        } else {
+            c.encode_utf16(&mut [0; 2]).iter().for_each(|unit| header.push_str(&format!("\\u{:04x}", unit)));
+        }
+    }
+    header
 }
 
 /// Converts a generic response in the format required by Warp framework
-pub fn to_warp(response: R) -> WithStatus<warp::reply::Json> {
-    warp::reply::with_status(
-        warp::reply::json(&response.message),
-        warp::http::StatusCode::from_u16(response.status).unwrap(),
-    )
+pub fn to_warp(response: R) -> warp::reply::Response {
+    let status = warp::http::StatusCode::from_u16(response.status).unwrap();
+    let attachment = match response.attachment {
+        Some(ref attachment) => attachment,
+        None => return warp::reply::with_status(warp::reply::json(&response.message), status).into_response(),
+    };
+
+    let mut builder = warp::http::Response::builder().status(status);
+    for (name, value) in attachment_headers(&response.message, attachment) {
+        builder = builder.header(name, value);
+    }
+    builder.body(attachment.content.clone().into()).unwrap()
 }
 
 /// Converts a generic response in the format required by the Vercel serverless functions
 pub fn to_vercel(response: R) -> Result<Vercel::Response<Vercel::Body>, Vercel::Error> {
-    Ok(Vercel::Response::builder()
+    let builder = Vercel::Response::builder()
         .status(response.status)
-        .header("content-type", "application/json")
         .header("Access-Control-Allow-Origin", "*")
         .header("Access-Control-Allow-Methods", "GET, POST, PATCH, PUT, DELETE, OPTIONS")
-        .header("Access-Control-Allow-Headers", "Content-Type, Authorization")
-        .body(response.message.to_string().into())?)
+        .header("Access-Control-Allow-Headers", "Content-Type, Authorization");
+
+    match response.attachment {
+        Some(attachment) => {
+            let mut builder = builder.header("Access-Control-Expose-Headers", "Content-Disposition, X-Message");
+            for (name, value) in attachment_headers(&response.message, &attachment) {
+                builder = builder.header(name, value);
+            }
+            Ok(builder.body(attachment.content.into())?)
+        }
+        None => Ok(builder.header("content-type", "application/json").body(response.message.to_string().into())?),
+    }
 }
@@ -2,7 +2,9 @@ use futures::stream::{StreamExt, TryStreamExt};
 use warp::{multipart::FormData, Rejection};
 
 pub mod controller;
+pub mod csv_autofix;
 pub mod csv_campaign_parser;
+pub mod csv_report;
 pub mod data_objects;
 pub mod services;
 pub mod utils;
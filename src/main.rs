@@ -1,17 +1,16 @@
-use futures::stream::{StreamExt, TryStreamExt};
-use warp::{multipart::FormData, Rejection};
-
-pub mod controller;
-mod csv_campaign_parser;
-mod data_objects;
-mod services;
-mod utils;
-
-type WebResult<T> = std::result::Result<T, Rejection>;
+use sablier_merkle_api::{
+    controller,
+    services::{cache::CampaignCache, store},
+};
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() {
-    let routes = controller::build_routes();
+    let store = store::from_env().unwrap_or_else(|error| {
+        eprintln!("Cannot start the server: {}", error);
+        std::process::exit(1);
+    });
+    let routes = controller::build_routes(store, Arc::new(CampaignCache::from_env()));
 
     // Run a web server on localhost:3000
     warp::serve(routes).run(([0, 0, 0, 0], 3000)).await;
@@ -1 +1,2 @@
-pub mod ipfs;
+pub mod cache;
+pub mod store;
@@ -0,0 +1,646 @@
+use lru::LruCache;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, HashMap},
+    mem::size_of,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    data_objects::dto::{
+        CampaignShardDto, PersistentCampaignDto, PersistentCampaignV1Dto, RecipientDto, ShardRecipientDto,
+        ShardedCampaignDto, CAMPAIGN_SCHEMA_VERSION,
+    },
+    services::store::{CampaignStore, StoreResult},
+    utils::{
+        leaf_schema::{LeafField, LeafSchema},
+        merkle::{MerkleTreeDump, MerkleTreeValue},
+        sharding::SHARDED_LAYOUT,
+    },
+};
+
+/// Default maximum size of the cached campaigns, in bytes
+pub const DEFAULT_CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+/// Cache of the requests served by the same process. The Vercel handlers go through it, as a warm function instance
+/// keeps it from one request to the next.
+static SHARED_CACHE: Lazy<CampaignCache> = Lazy::new(CampaignCache::from_env);
+
+/// Normalize an address so that it can be looked up in the address index of a campaign
+pub fn normalize_address(address: &str) -> String {
+    address.trim().to_lowercase()
+}
+
+/// Build the normalized address to recipient index map of the recipients. When an address appears more than once,
+/// the first recipient wins.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::{data_objects::dto::RecipientDto, services::cache::build_address_index};
+///
+/// let recipients = vec![
+///     RecipientDto { address: "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491".to_string(), amount: "1".to_string() },
+///     RecipientDto { address: "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc".to_string(), amount: "2".to_string() },
+/// ];
+/// let index = build_address_index(&recipients);
+/// assert_eq!(index["0xf976af93b0a5a9f55a7f285a3b5355b8575eb5bc"], 1);
+/// ```
+pub fn build_address_index(recipients: &[RecipientDto]) -> HashMap<String, usize> {
+    let mut index = HashMap::with_capacity(recipients.len());
+    for (i, recipient) in recipients.iter().enumerate() {
+        index.entry(normalize_address(&recipient.address)).or_insert(i);
+    }
+    index
+}
+
+/// A campaign downloaded from the store and parsed. The merkle tree is checked the first time it is needed.
+pub struct LoadedCampaign {
+    pub campaign: PersistentCampaignDto,
+    address_index: HashMap<String, usize>,
+    tree: OnceCell<Result<(), String>>,
+}
+
+impl LoadedCampaign {
+    /// Wrap a parsed campaign, taking over the address index persisted with it or building it for the campaigns that
+    /// were created without one.
+    pub fn new(mut campaign: PersistentCampaignDto) -> Self {
+        let address_index = match campaign.address_index.take() {
+            Some(index) if index.values().all(|i| *i < campaign.recipients.len()) => index,
+            _ => build_address_index(&campaign.recipients),
+        };
+        LoadedCampaign { campaign, address_index, tree: OnceCell::new() }
+    }
+
+    /// Get the index of the recipient with the specified address, ignoring its case.
+    pub fn index_of(&self, address: &str) -> Option<usize> {
+        let address = normalize_address(address);
+        self.address_index
+            .get(&address)
+            .copied()
+            .filter(|i| normalize_address(&self.campaign.recipients[*i].address) == address)
+    }
+
+    /// Get the merkle tree of the campaign, checking the embedded `standard-v1` dump on the first call so that a
+    /// malformed campaign file is reported as an error instead of a panic.
+    pub fn tree(&self) -> Result<&MerkleTreeDump, String> {
+        self.tree
+            .get_or_init(|| check_tree(&self.campaign))
+            .as_ref()
+            .map(|_| &self.campaign.merkle_tree)
+            .map_err(|e| e.clone())
+    }
+}
+
+/// A campaign file downloaded from the store, either a whole campaign or the manifest of a sharded campaign
+#[derive(Clone)]
+pub enum CampaignFile {
+    Single(Arc<LoadedCampaign>),
+    Sharded(Arc<ShardedCampaignDto>),
+}
+
+impl CampaignFile {
+    /// Parse a campaign file, telling the layouts apart through the `layout` member of the manifests and upgrading the
+    /// campaigns stored with a previous version of the schema.
+    pub fn parse(bytes: &[u8]) -> StoreResult<CampaignFile> {
+        let probe: CampaignFileProbe = serde_json::from_slice(bytes)?;
+        match probe.layout.as_deref() {
+            Some(SHARDED_LAYOUT) => Ok(CampaignFile::Sharded(Arc::new(serde_json::from_slice(bytes)?))),
+            Some(layout) => Err(format!("Unknown campaign layout {}", layout).into()),
+            None => Ok(CampaignFile::Single(Arc::new(LoadedCampaign::new(parse_campaign(bytes)?)))),
+        }
+    }
+
+    pub fn root(&self) -> &str {
+        match self {
+            CampaignFile::Single(loaded) => &loaded.campaign.root,
+            CampaignFile::Sharded(manifest) => &manifest.root,
+        }
+    }
+
+    pub fn leaf_schema(&self) -> &LeafSchema {
+        match self {
+            CampaignFile::Single(loaded) => &loaded.campaign.leaf_schema,
+            CampaignFile::Sharded(manifest) => &manifest.leaf_schema,
+        }
+    }
+}
+
+/// The members of a campaign file that tell its layout and schema version
+#[derive(Deserialize)]
+struct CampaignFileProbe {
+    #[serde(default)]
+    layout: Option<String>,
+    #[serde(default)]
+    version: Option<u32>,
+}
+
+/// Parse a single file campaign stored with any version of the schema, upgrading it to the latest one.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::{data_objects::dto::CAMPAIGN_SCHEMA_VERSION, services::cache::parse_campaign};
+///
+/// let v1 = br#"{"root": "root", "total_amount": "10", "number_of_recipients": 1, "merkle_tree": "{\"format\": \"standard-v1\", \"tree\": [], \"values\": [], \"leaf_encoding\": []}", "recipients": []}"#;
+/// let campaign = parse_campaign(v1).unwrap();
+/// assert_eq!(campaign.version, CAMPAIGN_SCHEMA_VERSION);
+/// assert_eq!(campaign.merkle_tree.format, "standard-v1");
+///
+/// let v3 = br#"{"version": 3, "root": "root"}"#;
+/// assert!(parse_campaign(v3).is_err());
+/// ```
+pub fn parse_campaign(bytes: &[u8]) -> StoreResult<PersistentCampaignDto> {
+    let probe: CampaignFileProbe = serde_json::from_slice(bytes)?;
+    match probe.version.unwrap_or(1) {
+        1 => Ok(serde_json::from_slice::<PersistentCampaignV1Dto>(bytes)?.into()),
+        CAMPAIGN_SCHEMA_VERSION => Ok(serde_json::from_slice(bytes)?),
+        version => Err(format!("Unsupported campaign schema version {}", version).into()),
+    }
+}
+
+/// Parse a shard of a sharded campaign, rejecting the versions of the schema this service cannot read.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::services::cache::parse_shard;
+///
+/// assert!(parse_shard(br#"{"root": "root", "recipients": []}"#).is_ok());
+/// assert!(parse_shard(br#"{"version": 3, "root": "root", "recipients": []}"#).is_err());
+/// ```
+pub fn parse_shard(bytes: &[u8]) -> StoreResult<CampaignShardDto> {
+    let probe: CampaignFileProbe = serde_json::from_slice(bytes)?;
+    match probe.version.unwrap_or(CAMPAIGN_SCHEMA_VERSION) {
+        CAMPAIGN_SCHEMA_VERSION => Ok(serde_json::from_slice(bytes)?),
+        version => Err(format!("Unsupported shard schema version {}", version).into()),
+    }
+}
+
+/// A shard of a sharded campaign downloaded from the store and indexed by address
+pub struct LoadedShard {
+    pub shard: CampaignShardDto,
+    address_index: HashMap<String, usize>,
+}
+
+impl LoadedShard {
+    pub fn new(shard: CampaignShardDto) -> Self {
+        let mut address_index = HashMap::with_capacity(shard.recipients.len());
+        for (i, recipient) in shard.recipients.iter().enumerate() {
+            address_index.entry(normalize_address(&recipient.address)).or_insert(i);
+        }
+        LoadedShard { shard, address_index }
+    }
+
+    /// Get the position inside the shard of the recipient with the specified address, ignoring its case.
+    pub fn index_of(&self, address: &str) -> Option<usize> {
+        self.address_index.get(&normalize_address(address)).copied()
+    }
+}
+
+/// Check that the merkle tree of a campaign is well formed and holds a value for every recipient.
+fn check_tree(campaign: &PersistentCampaignDto) -> Result<(), String> {
+    campaign.merkle_tree.validate()?;
+    if campaign.merkle_tree.values.len() < campaign.recipients.len() {
+        return Err(String::from("Invalid merkle tree: missing recipients"));
+    }
+    Ok(())
+}
+
+/// Estimate of the heap memory kept by a parsed value. The cache weighs its entries with it, as a parsed campaign takes
+/// more memory than its JSON file once its strings, merkle tree and address index are allocated.
+trait HeapSize {
+    fn heap_size(&self) -> usize;
+
+    /// Estimate of the whole memory kept by the value, itself included
+    fn memory_size(&self) -> usize
+    where
+        Self: Sized,
+    {
+        size_of::<Self>() + self.heap_size()
+    }
+}
+
+impl HeapSize for usize {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, HeapSize::heap_size)
+    }
+}
+
+impl<V: HeapSize> HeapSize for HashMap<String, V> {
+    fn heap_size(&self) -> usize {
+        // Every bucket holds an entry and a control byte, whether it is used or not
+        self.capacity() * (size_of::<(String, V)>() + 1)
+            + self.iter().map(|(key, value)| key.heap_size() + value.heap_size()).sum::<usize>()
+    }
+}
+
+impl HeapSize for BTreeMap<String, String> {
+    fn heap_size(&self) -> usize {
+        self.len() * size_of::<(String, String)>()
+            + self.iter().map(|(key, value)| key.heap_size() + value.heap_size()).sum::<usize>()
+    }
+}
+
+impl HeapSize for RecipientDto {
+    fn heap_size(&self) -> usize {
+        self.address.heap_size() + self.amount.heap_size()
+    }
+}
+
+impl HeapSize for ShardRecipientDto {
+    fn heap_size(&self) -> usize {
+        self.address.heap_size() + self.amount.heap_size() + self.proof.heap_size() + self.leaf.heap_size()
+    }
+}
+
+impl HeapSize for LeafField {
+    fn heap_size(&self) -> usize {
+        self.name.heap_size() + self.kind.heap_size()
+    }
+}
+
+impl HeapSize for MerkleTreeValue {
+    fn heap_size(&self) -> usize {
+        self.value.heap_size()
+    }
+}
+
+impl HeapSize for MerkleTreeDump {
+    fn heap_size(&self) -> usize {
+        self.format.heap_size() + self.tree.heap_size() + self.values.heap_size() + self.leaf_encoding.heap_size()
+    }
+}
+
+impl HeapSize for PersistentCampaignDto {
+    fn heap_size(&self) -> usize {
+        self.total_amount.heap_size()
+            + self.root.heap_size()
+            + self.merkle_tree.heap_size()
+            + self.leaf_schema.fields.heap_size()
+            + self.recipients.heap_size()
+            + self.address_index.heap_size()
+    }
+}
+
+impl HeapSize for LoadedCampaign {
+    fn heap_size(&self) -> usize {
+        self.campaign.heap_size() + self.address_index.heap_size()
+    }
+}
+
+impl HeapSize for ShardedCampaignDto {
+    fn heap_size(&self) -> usize {
+        self.layout.heap_size()
+            + self.total_amount.heap_size()
+            + self.root.heap_size()
+            + self.leaf_schema.fields.heap_size()
+            + self.shards.heap_size()
+    }
+}
+
+impl HeapSize for CampaignFile {
+    fn heap_size(&self) -> usize {
+        match self {
+            CampaignFile::Single(loaded) => loaded.memory_size(),
+            CampaignFile::Sharded(manifest) => manifest.memory_size(),
+        }
+    }
+}
+
+impl HeapSize for CampaignShardDto {
+    fn heap_size(&self) -> usize {
+        self.root.heap_size() + self.recipients.heap_size()
+    }
+}
+
+impl HeapSize for LoadedShard {
+    fn heap_size(&self) -> usize {
+        self.shard.heap_size() + self.address_index.heap_size()
+    }
+}
+
+/// Counters describing the usage of the campaign cache
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+type CachedFile = Arc<dyn Any + Send + Sync>;
+
+/// A download shared by the requests of the same file, which holds its outcome once it is done. The error is kept as
+/// a message so that every request waiting on it gets a copy.
+type PendingLoad = Arc<tokio::sync::OnceCell<Result<CachedFile, String>>>;
+
+struct CacheEntries {
+    files: LruCache<String, (CachedFile, usize)>,
+    bytes: usize,
+}
+
+/// Bounded least recently used cache of the downloaded campaign files, keyed by CID. The content behind a CID is
+/// immutable, so the entries never need to be invalidated. The size of an entry is an estimate of the memory taken by
+/// the parsed file, which is about one and a half times the size of the downloaded JSON for a typical campaign.
+///
+/// The concurrent requests of a file which is not cached yet wait on a single download instead of starting their own.
+pub struct CampaignCache {
+    max_bytes: usize,
+    entries: Mutex<CacheEntries>,
+    pending: Mutex<HashMap<(String, TypeId), PendingLoad>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CampaignCache {
+    pub fn new(max_bytes: usize) -> Self {
+        CampaignCache {
+            max_bytes,
+            entries: Mutex::new(CacheEntries { files: LruCache::unbounded(), bytes: 0 }),
+            pending: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Create a cache bounded by the `CAMPAIGN_CACHE_MAX_BYTES` environment variable. Setting it to 0 disables the
+    /// cache.
+    pub fn from_env() -> Self {
+        let max_bytes = std::env::var("CAMPAIGN_CACHE_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CACHE_MAX_BYTES);
+        CampaignCache::new(max_bytes)
+    }
+
+    /// Get the cache shared by the whole process, bounded by the `CAMPAIGN_CACHE_MAX_BYTES` environment variable.
+    pub fn shared() -> &'static CampaignCache {
+        &SHARED_CACHE
+    }
+
+    /// Get the campaign stored under the CID, downloading and parsing it only if it is not cached already. Sharded
+    /// campaigns cannot be loaded as a whole, so they are reported as errors.
+    pub async fn load(&self, store: &dyn CampaignStore, cid: &str) -> StoreResult<Arc<LoadedCampaign>> {
+        match self.load_file(store, cid).await? {
+            CampaignFile::Single(loaded) => Ok(loaded),
+            CampaignFile::Sharded(_) => Err("The campaign is sharded and cannot be loaded as a whole".into()),
+        }
+    }
+
+    /// Get the campaign file stored under the CID, whatever its layout.
+    pub async fn load_file(&self, store: &dyn CampaignStore, cid: &str) -> StoreResult<CampaignFile> {
+        let file = self.get_or_load(store, cid, CampaignFile::parse).await?;
+        Ok(file.as_ref().clone())
+    }
+
+    /// Get the shard of a sharded campaign stored under the CID.
+    pub async fn load_shard(&self, store: &dyn CampaignStore, cid: &str) -> StoreResult<Arc<LoadedShard>> {
+        self.get_or_load(store, cid, |bytes| Ok(LoadedShard::new(parse_shard(bytes)?))).await
+    }
+
+    async fn get_or_load<T, F>(&self, store: &dyn CampaignStore, cid: &str, parse: F) -> StoreResult<Arc<T>>
+    where
+        T: Any + Send + Sync + HeapSize,
+        F: FnOnce(&[u8]) -> StoreResult<T>,
+    {
+        let cached = self.entries.lock().unwrap().files.get(cid).map(|(file, _)| file.clone());
+        if let Some(file) = cached.and_then(|file| file.downcast::<T>().ok()) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(file);
+        }
+
+        let key = (cid.to_string(), TypeId::of::<T>());
+        let pending = self.pending.lock().unwrap().entry(key.clone()).or_default().clone();
+        let mut downloaded = false;
+        let outcome = pending
+            .get_or_init(|| async {
+                downloaded = true;
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                let load = async {
+                    let parsed = parse(&store.get(cid).await?)?;
+                    let size = parsed.memory_size();
+                    let file: CachedFile = Arc::new(parsed);
+                    self.insert(cid, file.clone(), size);
+                    Ok::<_, Box<dyn std::error::Error + Send + Sync>>(file)
+                };
+                load.await.map_err(|e| e.to_string())
+            })
+            .await
+            .clone();
+        if !downloaded {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // The outcome is settled, so the next requests go through the cache or retry a failed download
+        let mut pending_loads = self.pending.lock().unwrap();
+        if pending_loads.get(&key).is_some_and(|current| Arc::ptr_eq(current, &pending)) {
+            pending_loads.remove(&key);
+        }
+        drop(pending_loads);
+
+        let file = outcome?;
+        Ok(file.downcast::<T>().expect("the pending loads are keyed by type"))
+    }
+
+    fn insert(&self, cid: &str, file: CachedFile, size: usize) {
+        if size > self.max_bytes {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if let Some((_, previous_size)) = entries.files.put(cid.to_string(), (file, size)) {
+            entries.bytes -= previous_size;
+        }
+        entries.bytes += size;
+
+        while entries.bytes > self.max_bytes {
+            match entries.files.pop_lru() {
+                Some((_, (_, evicted_size))) => entries.bytes -= evicted_size,
+                None => break,
+            }
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let entries = self.entries.lock().unwrap();
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: entries.files.len(),
+            bytes: entries.bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::store::{memory::MemoryStore, put_json};
+
+    fn create_campaign(root: &str) -> PersistentCampaignDto {
+        PersistentCampaignDto {
+            version: CAMPAIGN_SCHEMA_VERSION,
+            total_amount: "128".to_string(),
+            number_of_recipients: 4,
+            root: root.to_string(),
+            merkle_tree: MerkleTreeDump::default(),
+            leaf_schema: LeafSchema::default(),
+            recipients: Vec::new(),
+            address_index: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn load_counts_hits_and_misses() {
+        let store = MemoryStore::default();
+        let cache = CampaignCache::new(DEFAULT_CACHE_MAX_BYTES);
+        let cid = put_json(&store, &create_campaign("root")).await.unwrap();
+
+        assert_eq!(cache.load(&store, &cid).await.unwrap().campaign.root, "root");
+        assert_eq!(cache.load(&store, &cid).await.unwrap().campaign.root, "root");
+        assert!(cache.load(&store, "missing_cid").await.is_err());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 1));
+    }
+
+    #[tokio::test]
+    async fn load_weighs_the_parsed_campaign() {
+        let mut campaign = create_campaign("root");
+        campaign.recipients = (0..256)
+            .map(|i| RecipientDto { address: format!("0x{:040x}", i + 1), amount: (100 * (i + 1)).to_string() })
+            .collect();
+        let values = campaign
+            .recipients
+            .iter()
+            .enumerate()
+            .map(|(i, recipient)| vec![i.to_string(), recipient.address.clone(), recipient.amount.clone()])
+            .collect();
+        campaign.merkle_tree = MerkleTreeDump::build(values, LeafSchema::default().types()).unwrap();
+        campaign.address_index = Some(build_address_index(&campaign.recipients));
+
+        let store = MemoryStore::default();
+        let cache = CampaignCache::new(DEFAULT_CACHE_MAX_BYTES);
+        let cid = put_json(&store, &campaign).await.unwrap();
+        let loaded = cache.load(&store, &cid).await.unwrap();
+
+        let file_size = store.get(&cid).await.unwrap().len();
+        assert_eq!(cache.stats().bytes, CampaignFile::Single(loaded).memory_size());
+        assert!(cache.stats().bytes > file_size, "{} bytes for a {} bytes file", cache.stats().bytes, file_size);
+    }
+
+    #[tokio::test]
+    async fn load_evicts_least_recently_used() {
+        let store = MemoryStore::default();
+        let first = put_json(&store, &create_campaign("first")).await.unwrap();
+        let second = put_json(&store, &create_campaign("second")).await.unwrap();
+        let size = CampaignFile::parse(&store.get(&second).await.unwrap()).unwrap().memory_size();
+        let cache = CampaignCache::new(size + 1);
+
+        cache.load(&store, &first).await.unwrap();
+        cache.load(&store, &second).await.unwrap();
+        cache.load(&store, &first).await.unwrap();
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (0, 3, 1));
+    }
+
+    /// Store which yields before every download, so that the concurrent loads overlap, and counts them
+    #[derive(Default)]
+    struct SlowStore {
+        inner: MemoryStore,
+        gets: AtomicU64,
+    }
+
+    #[async_trait::async_trait]
+    impl CampaignStore for SlowStore {
+        async fn put(&self, data: Vec<u8>) -> StoreResult<String> {
+            self.inner.put(data).await
+        }
+
+        async fn get(&self, cid: &str) -> StoreResult<Vec<u8>> {
+            self.gets.fetch_add(1, Ordering::Relaxed);
+            tokio::task::yield_now().await;
+            self.inner.get(cid).await
+        }
+
+        async fn delete(&self, cid: &str) -> StoreResult<()> {
+            self.inner.delete(cid).await
+        }
+
+        async fn exists(&self, cid: &str) -> StoreResult<bool> {
+            self.inner.exists(cid).await
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_loads_share_one_download() {
+        let store = SlowStore::default();
+        // Nothing fits into the cache, so only the shared download can spare the other requests
+        let cache = CampaignCache::new(0);
+        let cid = put_json(&store, &create_campaign("root")).await.unwrap();
+
+        let loads = futures::future::join_all((0..8).map(|_| cache.load(&store, &cid))).await;
+        assert!(loads.iter().all(|loaded| loaded.as_ref().unwrap().campaign.root == "root"));
+        assert_eq!(store.gets.load(Ordering::Relaxed), 1);
+        assert_eq!((cache.stats().hits, cache.stats().misses), (7, 1));
+        assert!(cache.pending.lock().unwrap().is_empty());
+
+        cache.load(&store, &cid).await.unwrap();
+        assert_eq!(store.gets.load(Ordering::Relaxed), 2);
+
+        let loads = futures::future::join_all((0..2).map(|_| cache.load(&store, "missing_cid"))).await;
+        assert!(loads.iter().all(|loaded| loaded.is_err()));
+        assert_eq!(store.gets.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn index_of_uses_persisted_or_rebuilt_index() {
+        let mut campaign = create_campaign("root");
+        campaign.recipients = vec![
+            RecipientDto { address: "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491".to_string(), amount: "1".to_string() },
+            RecipientDto { address: "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc".to_string(), amount: "2".to_string() },
+        ];
+        campaign.address_index = Some(build_address_index(&campaign.recipients));
+        let loaded = LoadedCampaign::new(campaign);
+
+        assert_eq!(loaded.index_of(" 0xF976AF93B0A5A9F55A7F285A3B5355B8575EB5BC "), Some(1));
+        assert_eq!(loaded.index_of("0x0000000000000000000000000000000000000001"), None);
+
+        let mut campaign = loaded.campaign;
+        campaign.address_index = Some(HashMap::from([("0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491".to_string(), 7)]));
+        let loaded = LoadedCampaign::new(campaign);
+
+        assert_eq!(loaded.index_of("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491"), Some(0));
+    }
+
+    #[test]
+    fn tree_errors_instead_of_panicking() {
+        let campaign = LoadedCampaign::new(create_campaign("root"));
+        assert!(campaign.tree().is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use dotenvy::dotenv;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use std::{error::Error, sync::Arc};
+
+pub mod filesystem;
+pub mod kubo;
+pub mod local_ipfs;
+pub mod memory;
+pub mod pinata;
+
+use filesystem::FilesystemStore;
+use kubo::KuboStore;
+use local_ipfs::LocalIpfsStore;
+use memory::MemoryStore;
+use pinata::PinataStore;
+
+/// Result type returned by every campaign store operation
+pub type StoreResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Generic trait for a backend able to persist airstream campaigns. Every campaign is addressed by the content id
+/// returned when it was stored.
+#[async_trait]
+pub trait CampaignStore: Send + Sync {
+    /// Store the raw content and return its content id.
+    async fn put(&self, data: Vec<u8>) -> StoreResult<String>;
+    /// Retrieve the raw content stored under the content id.
+    async fn get(&self, cid: &str) -> StoreResult<Vec<u8>>;
+    /// Remove the content stored under the content id.
+    async fn delete(&self, cid: &str) -> StoreResult<()>;
+    /// Check if there is any content stored under the content id.
+    async fn exists(&self, cid: &str) -> StoreResult<bool>;
+}
+
+/// Serialize a value as JSON and store it, returning the content id.
+pub async fn put_json<T: Serialize>(store: &dyn CampaignStore, data: &T) -> StoreResult<String> {
+    let bytes = serde_json::to_vec(&json!(data))?;
+    store.put(bytes).await
+}
+
+/// Retrieve the content stored under the content id and parse it into the specified struct.
+pub async fn get_json<T: DeserializeOwned>(store: &dyn CampaignStore, cid: &str) -> StoreResult<T> {
+    let bytes = store.get(cid).await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Build the campaign store selected through the `CAMPAIGN_STORE` environment variable. The supported values are
+/// `pinata` (default), `kubo`, `local-ipfs`, `filesystem` and `memory`. An unsupported value is returned as an error
+/// instead of taking the whole process down.
+pub fn from_env() -> Result<Arc<dyn CampaignStore>, String> {
+    dotenv().ok();
+    let kind = std::env::var("CAMPAIGN_STORE").unwrap_or_else(|_| String::from("pinata"));
+
+    match kind.to_lowercase().as_str() {
+        "pinata" => Ok(Arc::new(PinataStore::from_env())),
+        "kubo" => Ok(Arc::new(KuboStore::from_env())),
+        "local-ipfs" => Ok(Arc::new(LocalIpfsStore::from_env()?)),
+        "filesystem" => Ok(Arc::new(FilesystemStore::from_env())),
+        "memory" => Ok(Arc::new(MemoryStore::default())),
+        other => Err(format!("Unsupported CAMPAIGN_STORE value: {}", other)),
+    }
+}
+
+/// Compute the content id used by the stores that do not rely on an IPFS node.
+pub(crate) fn content_id(data: &[u8]) -> String {
+    hex::encode(ethers_core::utils::keccak256(data))
+}
+
+/// Checks if a content id can be safely used as a key for a local store.
+pub(crate) fn is_valid_content_id(cid: &str) -> bool {
+    !cid.is_empty() && cid.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data_objects::dto::{PersistentCampaignDto, CAMPAIGN_SCHEMA_VERSION},
+        utils::{leaf_schema::LeafSchema, merkle::MerkleTreeDump},
+    };
+
+    #[tokio::test]
+    async fn json_round_trip() {
+        let store = MemoryStore::default();
+        let data = PersistentCampaignDto {
+            version: CAMPAIGN_SCHEMA_VERSION,
+            total_amount: "128".to_string(),
+            number_of_recipients: 4,
+            root: "test_root".to_string(),
+            merkle_tree: MerkleTreeDump::default(),
+            leaf_schema: LeafSchema::default(),
+            recipients: Vec::new(),
+            address_index: None,
+        };
+
+        let cid = put_json(&store, &data).await.unwrap();
+        let result: PersistentCampaignDto = get_json(&store, &cid).await.unwrap();
+
+        assert_eq!(result.root, data.root);
+        assert_eq!(result.number_of_recipients, data.number_of_recipients);
+    }
+
+    #[test]
+    fn unknown_store_is_an_error() {
+        std::env::set_var("CAMPAIGN_STORE", "unknown");
+        assert_eq!(from_env().err().unwrap(), "Unsupported CAMPAIGN_STORE value: unknown");
+    }
+
+    #[test]
+    fn content_id_validation() {
+        assert!(is_valid_content_id(&content_id(b"data")));
+        assert!(!is_valid_content_id("../data"));
+        assert!(!is_valid_content_id(""));
+    }
+}
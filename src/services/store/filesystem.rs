@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use super::{content_id, is_valid_content_id, CampaignStore, StoreResult};
+
+/// Campaign store that keeps every campaign as a file inside a local directory.
+pub struct FilesystemStore {
+    pub root: PathBuf,
+}
+
+impl FilesystemStore {
+    /// Create a store on top of the directory specified by the `CAMPAIGN_STORE_PATH` environment variable.
+    pub fn from_env() -> Self {
+        let root = std::env::var("CAMPAIGN_STORE_PATH").expect("CAMPAIGN_STORE_PATH must be set");
+        FilesystemStore { root: PathBuf::from(root) }
+    }
+
+    fn path(&self, cid: &str) -> StoreResult<PathBuf> {
+        if !is_valid_content_id(cid) {
+            return Err(format!("Invalid content id {}", cid).into());
+        }
+        Ok(self.root.join(cid))
+    }
+}
+
+#[async_trait]
+impl CampaignStore for FilesystemStore {
+    async fn put(&self, data: Vec<u8>) -> StoreResult<String> {
+        let cid = content_id(&data);
+        tokio::fs::create_dir_all(&self.root).await?;
+        tokio::fs::write(self.path(&cid)?, data).await?;
+        Ok(cid)
+    }
+
+    async fn get(&self, cid: &str) -> StoreResult<Vec<u8>> {
+        Ok(tokio::fs::read(self.path(cid)?).await?)
+    }
+
+    async fn delete(&self, cid: &str) -> StoreResult<()> {
+        let path = self.path(cid)?;
+        if tokio::fs::try_exists(&path).await? {
+            tokio::fs::remove_file(path).await?;
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, cid: &str) -> StoreResult<bool> {
+        Ok(tokio::fs::try_exists(self.path(cid)?).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn put_get_delete() {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let store = FilesystemStore { root: root.clone() };
+
+        let cid = store.put(b"campaign".to_vec()).await.unwrap();
+        assert!(store.exists(&cid).await.unwrap());
+        assert_eq!(store.get(&cid).await.unwrap(), b"campaign".to_vec());
+
+        store.delete(&cid).await.unwrap();
+        assert!(!store.exists(&cid).await.unwrap());
+        assert!(store.get("../campaign").await.is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
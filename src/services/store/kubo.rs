@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+
+use super::{CampaignStore, StoreResult};
+
+/// The success response after an add request to the Kubo RPC API
+#[derive(Deserialize, Debug)]
+struct KuboAddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+/// Campaign store that adds and pins the campaigns on a self-hosted IPFS node through the Kubo RPC API.
+pub struct KuboStore {
+    pub api_url: String,
+}
+
+impl KuboStore {
+    /// Create a store on top of the node specified by the `KUBO_API_URL` environment variable.
+    pub fn from_env() -> Self {
+        KuboStore { api_url: std::env::var("KUBO_API_URL").expect("KUBO_API_URL must be set") }
+    }
+
+    async fn call(&self, command: &str, cid: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let api_endpoint = format!("{}/api/v0/{}", self.api_url, command);
+        reqwest::Client::new().post(api_endpoint).query(&[("arg", cid)]).send().await
+    }
+}
+
+#[async_trait]
+impl CampaignStore for KuboStore {
+    async fn put(&self, data: Vec<u8>) -> StoreResult<String> {
+        let api_endpoint = format!("{}/api/v0/add?pin=true", self.api_url);
+
+        let part = Part::bytes(data).file_name("data.json").mime_str("application/json")?;
+        let form = Form::new().part("file", part);
+
+        let response = reqwest::Client::new().post(api_endpoint).multipart(form).send().await?.error_for_status()?;
+        let added: KuboAddResponse = response.json().await?;
+        Ok(added.hash)
+    }
+
+    async fn get(&self, cid: &str) -> StoreResult<Vec<u8>> {
+        let response = self.call("cat", cid).await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn delete(&self, cid: &str) -> StoreResult<()> {
+        self.call("pin/rm", cid).await?.error_for_status()?;
+        Ok(())
+    }
+
+    /// Kubo answers with an error status when the content is not pinned
+    async fn exists(&self, cid: &str) -> StoreResult<bool> {
+        let response = self.call("pin/ls", cid).await?;
+        Ok(response.status().is_success())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::async_test::SERVER;
+
+    #[tokio::test]
+    async fn put_and_get() {
+        let mut server = SERVER.lock().await;
+        let store = KuboStore { api_url: server.url() };
+
+        let add_mock = server
+            .mock("POST", "/api/v0/add?pin=true")
+            .with_status(200)
+            .with_body(r#"{"Name": "data.json", "Hash": "test_hash", "Size": "12"}"#)
+            .create();
+        let cat_mock = server.mock("POST", "/api/v0/cat?arg=test_hash").with_status(200).with_body("campaign").create();
+
+        assert_eq!(store.put(b"campaign".to_vec()).await.unwrap(), "test_hash");
+        assert_eq!(store.get("test_hash").await.unwrap(), b"campaign".to_vec());
+        add_mock.assert();
+        cat_mock.assert();
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn exists_not_pinned() {
+        let mut server = SERVER.lock().await;
+        let store = KuboStore { api_url: server.url() };
+
+        let mock = server
+            .mock("POST", "/api/v0/pin/ls?arg=missing_cid")
+            .with_status(500)
+            .with_body(r#"{"Message": "path 'missing_cid' is not pinned", "Code": 0, "Type": "error"}"#)
+            .create();
+
+        assert!(!store.exists("missing_cid").await.unwrap());
+        mock.assert();
+        drop(server);
+    }
+}
@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use super::{is_valid_content_id, CampaignStore, StoreResult};
+use crate::utils::unixfs::{cid_from_string, decode_node, encode_file, is_raw, CidVersion};
+
+/// Campaign store that encodes the campaigns as UnixFS files and keeps their blocks inside a local directory. The
+/// CIDs are computed locally and are identical to the ones an IPFS node or Pinata would return for the same content.
+pub struct LocalIpfsStore {
+    pub root: PathBuf,
+    pub cid_version: CidVersion,
+}
+
+impl LocalIpfsStore {
+    /// Create a store on top of the directory specified by the `CAMPAIGN_STORE_PATH` environment variable. The CID
+    /// version is read from `LOCAL_IPFS_CID_VERSION` and defaults to 0, like Pinata does.
+    pub fn from_env() -> Result<Self, String> {
+        let root = std::env::var("CAMPAIGN_STORE_PATH").expect("CAMPAIGN_STORE_PATH must be set");
+        let cid_version = match std::env::var("LOCAL_IPFS_CID_VERSION").as_deref() {
+            Ok("1") => CidVersion::V1,
+            Ok("0") | Err(_) => CidVersion::V0,
+            Ok(other) => return Err(format!("Unsupported LOCAL_IPFS_CID_VERSION value: {}", other)),
+        };
+        Ok(LocalIpfsStore { root: PathBuf::from(root), cid_version })
+    }
+
+    fn path(&self, cid: &str) -> StoreResult<PathBuf> {
+        if !is_valid_content_id(cid) {
+            return Err(format!("Invalid content id {}", cid).into());
+        }
+        Ok(self.root.join("blocks").join(cid))
+    }
+
+    /// Collect the CIDs of all the blocks reachable from the root, the root included.
+    async fn collect_blocks(&self, cid: &str) -> StoreResult<Vec<String>> {
+        let mut cids = vec![cid.to_string()];
+        let mut position = 0;
+
+        while position < cids.len() {
+            if !is_raw(&cid_from_string(&cids[position])?) {
+                let block = tokio::fs::read(self.path(&cids[position])?).await?;
+                cids.extend(decode_node(&block)?.links);
+            }
+            position += 1;
+        }
+
+        Ok(cids)
+    }
+}
+
+#[async_trait]
+impl CampaignStore for LocalIpfsStore {
+    async fn put(&self, data: Vec<u8>) -> StoreResult<String> {
+        let encoded = encode_file(&data, self.cid_version);
+        tokio::fs::create_dir_all(self.root.join("blocks")).await?;
+
+        for block in encoded.blocks {
+            tokio::fs::write(self.path(&block.cid)?, block.data).await?;
+        }
+
+        Ok(encoded.cid)
+    }
+
+    async fn get(&self, cid: &str) -> StoreResult<Vec<u8>> {
+        let mut content = Vec::new();
+        // Depth first traversal keeping the links order, so the chunks are concatenated in the right order
+        let mut pending = vec![cid.to_string()];
+
+        while let Some(current) = pending.pop() {
+            let block = tokio::fs::read(self.path(&current)?).await?;
+            if is_raw(&cid_from_string(&current)?) {
+                content.extend_from_slice(&block);
+                continue;
+            }
+
+            let node = decode_node(&block)?;
+            content.extend_from_slice(&node.data);
+            pending.extend(node.links.into_iter().rev());
+        }
+
+        Ok(content)
+    }
+
+    async fn delete(&self, cid: &str) -> StoreResult<()> {
+        if !self.exists(cid).await? {
+            return Ok(());
+        }
+
+        for block in self.collect_blocks(cid).await? {
+            let path = self.path(&block)?;
+            if tokio::fs::try_exists(&path).await? {
+                tokio::fs::remove_file(path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, cid: &str) -> StoreResult<bool> {
+        Ok(tokio::fs::try_exists(self.path(cid)?).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::unixfs::CHUNK_SIZE;
+
+    fn create_store(cid_version: CidVersion) -> LocalIpfsStore {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        LocalIpfsStore { root, cid_version }
+    }
+
+    #[tokio::test]
+    async fn put_returns_ipfs_cid() {
+        let store = create_store(CidVersion::V0);
+
+        let cid = store.put(b"hello world\n".to_vec()).await.unwrap();
+        assert_eq!(cid, "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o");
+        assert_eq!(store.get(&cid).await.unwrap(), b"hello world\n".to_vec());
+
+        std::fs::remove_dir_all(store.root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn put_get_delete_multiple_chunks() {
+        for cid_version in [CidVersion::V0, CidVersion::V1] {
+            let store = create_store(cid_version);
+            let content: Vec<u8> = (0..CHUNK_SIZE * 3 + 17).map(|i| (i % 251) as u8).collect();
+
+            let cid = store.put(content.clone()).await.unwrap();
+            assert!(store.exists(&cid).await.unwrap());
+            assert_eq!(store.get(&cid).await.unwrap(), content);
+
+            store.delete(&cid).await.unwrap();
+            assert!(!store.exists(&cid).await.unwrap());
+            assert_eq!(std::fs::read_dir(store.root.join("blocks")).unwrap().count(), 0);
+
+            std::fs::remove_dir_all(store.root).unwrap();
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Mutex};
+
+use super::{content_id, CampaignStore, StoreResult};
+
+/// Campaign store that keeps every campaign in memory. Useful for tests and for running the API offline.
+#[derive(Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl CampaignStore for MemoryStore {
+    async fn put(&self, data: Vec<u8>) -> StoreResult<String> {
+        let cid = content_id(&data);
+        self.entries.lock().unwrap().insert(cid.clone(), data);
+        Ok(cid)
+    }
+
+    async fn get(&self, cid: &str) -> StoreResult<Vec<u8>> {
+        match self.entries.lock().unwrap().get(cid) {
+            Some(data) => Ok(data.clone()),
+            None => Err(format!("No content stored under {}", cid).into()),
+        }
+    }
+
+    async fn delete(&self, cid: &str) -> StoreResult<()> {
+        self.entries.lock().unwrap().remove(cid);
+        Ok(())
+    }
+
+    async fn exists(&self, cid: &str) -> StoreResult<bool> {
+        Ok(self.entries.lock().unwrap().contains_key(cid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn put_get_delete() {
+        let store = MemoryStore::default();
+
+        let cid = store.put(b"campaign".to_vec()).await.unwrap();
+        assert!(store.exists(&cid).await.unwrap());
+        assert_eq!(store.get(&cid).await.unwrap(), b"campaign".to_vec());
+
+        store.delete(&cid).await.unwrap();
+        assert!(!store.exists(&cid).await.unwrap());
+        assert!(store.get(&cid).await.is_err());
+    }
+}
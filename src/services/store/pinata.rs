@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+
+use super::{CampaignStore, StoreResult};
+
+/// The success response after an upload request to Pinata
+#[derive(Deserialize, Debug)]
+pub struct PinataSuccess {
+    #[serde(rename = "IpfsHash")]
+    pub ipfs_hash: String,
+}
+
+/// The response of the Pinata pin list endpoint
+#[derive(Deserialize, Debug)]
+struct PinataPinList {
+    count: u64,
+}
+
+/// Deserialize the text response returned by Pinata API into PinataSuccess
+///
+/// # Examples
+///
+/// ```
+/// use serde;
+/// use sablier_merkle_api::services::store::pinata::{try_deserialize_pinata_response, PinataSuccess};
+///
+/// let result_ok: Result<PinataSuccess, serde_json::Error> = try_deserialize_pinata_response(r#"{"IpfsHash": "test_hash", "PinSize": 123, "Timestamp": "2023-04-05T00:00:00Z"}"#);
+/// let result_error: Result<PinataSuccess, serde_json::Error> = try_deserialize_pinata_response("Error message");
+/// assert!(result_ok.is_ok());
+/// assert!(result_error.is_err());
+/// ```
+pub fn try_deserialize_pinata_response(response_body: &str) -> Result<PinataSuccess, serde_json::Error> {
+    let success = serde_json::from_str::<PinataSuccess>(response_body)?;
+    Ok(success)
+}
+
+/// Campaign store that pins the campaigns to IPFS through Pinata and downloads them through a Pinata gateway.
+pub struct PinataStore {
+    pub api_key: String,
+    pub secret_api_key: String,
+    pub api_server: String,
+    pub gateway: String,
+    pub access_token: String,
+}
+
+impl PinataStore {
+    /// Create a store configured through the Pinata environment variables.
+    pub fn from_env() -> Self {
+        PinataStore {
+            api_key: std::env::var("PINATA_API_KEY").expect("PINATA_API_KEY must be set"),
+            secret_api_key: std::env::var("PINATA_SECRET_API_KEY").expect("PINATA_SECRET_API_KEY must be set"),
+            api_server: std::env::var("PINATA_API_SERVER").expect("PINATA_API_SERVER must be set"),
+            gateway: std::env::var("IPFS_GATEWAY").expect("IPFS_GATEWAY must be set"),
+            access_token: std::env::var("PINATA_ACCESS_TOKEN").expect("PINATA_ACCESS_TOKEN must be set"),
+        }
+    }
+}
+
+#[async_trait]
+impl CampaignStore for PinataStore {
+    /// Upload and pin the content to IPFS
+    async fn put(&self, data: Vec<u8>) -> StoreResult<String> {
+        let client = reqwest::Client::new();
+        let api_endpoint = format!("{}/pinning/pinFileToIPFS", self.api_server);
+
+        let part = Part::bytes(data).file_name("data.json").mime_str("application/json")?;
+        let form = Form::new().part("file", part);
+
+        let response = client
+            .post(api_endpoint)
+            .header("pinata_api_key", &self.api_key)
+            .header("pinata_secret_api_key", &self.secret_api_key)
+            .multipart(form)
+            .send()
+            .await?;
+
+        let text_response = response.text().await?;
+        let deserialized_response = try_deserialize_pinata_response(&text_response)?;
+        Ok(deserialized_response.ipfs_hash)
+    }
+
+    /// Download the content from a specified CID through the Pinata gateway
+    async fn get(&self, cid: &str) -> StoreResult<Vec<u8>> {
+        let ipfs_url = format!("{}/{}?pinataGatewayToken={}", self.gateway, cid, self.access_token);
+        let response = reqwest::get(&ipfs_url).await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Unpin the content from Pinata
+    async fn delete(&self, cid: &str) -> StoreResult<()> {
+        let client = reqwest::Client::new();
+        let api_endpoint = format!("{}/pinning/unpin/{}", self.api_server, cid);
+
+        client
+            .delete(api_endpoint)
+            .header("pinata_api_key", &self.api_key)
+            .header("pinata_secret_api_key", &self.secret_api_key)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Check if the content is pinned on Pinata
+    async fn exists(&self, cid: &str) -> StoreResult<bool> {
+        let client = reqwest::Client::new();
+        let api_endpoint = format!("{}/data/pinList?hashContains={}&status=pinned", self.api_server, cid);
+
+        let response = client
+            .get(api_endpoint)
+            .header("pinata_api_key", &self.api_key)
+            .header("pinata_secret_api_key", &self.secret_api_key)
+            .send()
+            .await?
+            .error_for_status()?;
+        let pin_list: PinataPinList = response.json().await?;
+        Ok(pin_list.count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::async_test::{setup_env_vars, SERVER};
+
+    #[test]
+    fn try_deserialize_pinata_response_success() {
+        let result: Result<PinataSuccess, serde_json::Error> = try_deserialize_pinata_response(
+            r#"{"IpfsHash": "test_hash", "PinSize": 123, "Timestamp": "2023-04-05T00:00:00Z"}"#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn try_deserialize_pinata_response_fail() {
+        let result: Result<PinataSuccess, serde_json::Error> = try_deserialize_pinata_response("Error message");
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_put_ok() {
+        let mut server = SERVER.lock().await;
+        setup_env_vars(&server);
+        // Set up mock server
+        let mock = server
+            .mock("POST", "/pinning/pinFileToIPFS")
+            .with_status(200)
+            .with_body(r#"{"IpfsHash": "test_hash", "PinSize": 123, "Timestamp": "2021-01-01T00:00:00Z"}"#)
+            .create();
+
+        let result = PinataStore::from_env().put(b"{}".to_vec()).await;
+
+        assert_eq!(result.unwrap(), "test_hash");
+        mock.assert();
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_put_error() {
+        let mut server = SERVER.lock().await;
+
+        setup_env_vars(&server);
+        // Set up mock server
+        let mock = server
+            .mock("POST", "/pinning/pinFileToIPFS")
+            .with_status(500)
+            .with_body(r#"{"code": "500", "message": "Internal server error"}"#)
+            .create();
+
+        let result = PinataStore::from_env().put(b"{}".to_vec()).await;
+
+        assert!(result.is_err());
+        mock.assert();
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_get_success() {
+        let mut server = SERVER.lock().await;
+
+        setup_env_vars(&server);
+
+        // Set up mock server
+        let mock = server
+            .mock("GET", "/valid_cid?pinataGatewayToken=mock_pinata_access_token")
+            .with_status(200)
+            .with_body(r#"{"IpfsHash": "test_hash", "PinSize": 123, "Timestamp": "2021-01-01T00:00:00Z"}"#)
+            .create();
+
+        let result = PinataStore::from_env().get("valid_cid").await;
+        assert!(result.is_ok());
+        mock.assert();
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_get_error() {
+        let mut server = SERVER.lock().await;
+
+        setup_env_vars(&server);
+
+        // Set up mock server
+        let mock = server
+            .mock("GET", "/valid_cid?pinataGatewayToken=mock_pinata_access_token")
+            .with_status(500)
+            .with_body(r#"{"code": "500", "message": "Internal server error"}"#)
+            .create();
+
+        let result = PinataStore::from_env().get("valid_cid").await;
+        assert!(result.is_err());
+        mock.assert();
+        drop(server);
+    }
+
+    #[tokio::test]
+    async fn test_exists() {
+        let mut server = SERVER.lock().await;
+
+        setup_env_vars(&server);
+
+        let mock = server
+            .mock("GET", "/data/pinList?hashContains=valid_cid&status=pinned")
+            .with_status(200)
+            .with_body(r#"{"count": 1, "rows": []}"#)
+            .create();
+
+        let result = PinataStore::from_env().exists("valid_cid").await;
+        assert!(result.unwrap());
+        mock.assert();
+        drop(server);
+    }
+}
@@ -19,4 +19,11 @@ pub mod async_test {
         std::env::set_var("PINATA_API_SERVER", server_host);
     }
 }
+pub mod campaign_lints;
+pub mod campaign_rules;
+pub mod csv_dialect;
 pub mod csv_validator;
+pub mod leaf_schema;
+pub mod merkle;
+pub mod sharding;
+pub mod unixfs;
@@ -0,0 +1,150 @@
+use ethers_core::types::U256;
+use ethers_rs::{Address, Eip55};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::utils::csv_validator::checksum_mismatch;
+
+/// Rule of the warnings about the zero address
+pub const ZERO_ADDRESS_RULE: &str = "zero-address";
+/// Rule of the warnings about the well known burn addresses
+pub const BURN_ADDRESS_RULE: &str = "burn-address";
+/// Rule of the warnings about the addresses of the precompiled contracts
+pub const PRECOMPILE_ADDRESS_RULE: &str = "precompile-address";
+/// Rule of the warnings about the mixed case addresses which do not match their EIP-55 checksum
+pub const CHECKSUM_RULE: &str = "checksum-mismatch";
+/// Rule of the warnings about the addresses written with another case in an earlier row
+pub const ADDRESS_CASE_RULE: &str = "address-case";
+/// Rule of the warnings about the amounts far away from the median amount
+pub const AMOUNT_OUTLIER_RULE: &str = "amount-outlier";
+/// Rule of the warnings about the amounts ending with the digits left by a floating point rounding
+pub const EXCESS_PRECISION_RULE: &str = "excess-precision";
+
+/// Addresses commonly used to burn tokens
+const BURN_ADDRESSES: [&str; 2] =
+    ["0x000000000000000000000000000000000000dead", "0xdead000000000000000042069420694206942069"];
+
+/// Highest address of the precompiled contracts, up to the BLS12-381 ones
+const LAST_PRECOMPILE: u64 = 0x11;
+
+/// Factor between an amount and the median amount above which the amount is reported as an outlier
+const OUTLIER_FACTOR: u64 = 100;
+
+/// Number of recipients below which the median amount is not meaningful enough to look for outliers
+const MIN_OUTLIER_SAMPLE: usize = 10;
+
+/// Decimal parts ending with a long run of zeros or nines, like the amounts written out of a floating point number
+static EXCESS_PRECISION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\.\d*[1-9](0{6,}\d{1,3}|9{6,}\d{0,3})$").unwrap());
+
+/// Finding of a lint rule, reported as a warning which does not prevent the campaign from being created
+#[derive(Debug, PartialEq)]
+pub struct Lint {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl Lint {
+    fn new(rule: &'static str, message: &str) -> Lint {
+        Lint { rule, message: message.to_string() }
+    }
+}
+
+/// Look for the addresses which are valid but unlikely to belong to a recipient. The address should already be
+/// validated.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::campaign_lints::{lint_address, CHECKSUM_RULE, ZERO_ADDRESS_RULE};
+///
+/// assert_eq!(lint_address("0x0000000000000000000000000000000000000000").unwrap().rule, ZERO_ADDRESS_RULE);
+/// assert_eq!(lint_address("0x9aD7CAD4F10D0c3f875b8a2fd292590490c9f491").unwrap().rule, CHECKSUM_RULE);
+/// assert!(lint_address("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491").is_none());
+/// assert!(lint_address("0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491").is_none());
+/// ```
+pub fn lint_address(address: &str) -> Option<Lint> {
+    let parsed = Address::from_str(address, false).ok()?;
+    let lowercase = format!("0x{}", address.trim_start_matches("0x").to_lowercase());
+    let value = U256::from_big_endian(&parsed.0);
+
+    if value.is_zero() {
+        return Some(Lint::new(ZERO_ADDRESS_RULE, "The zero address cannot claim its tokens"));
+    }
+    if BURN_ADDRESSES.contains(&lowercase.as_str()) {
+        return Some(Lint::new(BURN_ADDRESS_RULE, "This address is commonly used to burn tokens"));
+    }
+    if value <= LAST_PRECOMPILE.into() {
+        return Some(Lint::new(PRECOMPILE_ADDRESS_RULE, "This address belongs to a precompiled contract"));
+    }
+
+    checksum_mismatch(address).map(|checksum| Lint {
+        rule: CHECKSUM_RULE,
+        message: format!("The address does not match its EIP-55 checksum, which is {}", checksum),
+    })
+}
+
+/// Look for the amounts ending with the digits left by a floating point rounding, like `0.30000000000000004`, which
+/// usually come from a spreadsheet formula. The amount should already be validated.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::campaign_lints::lint_amount;
+///
+/// assert!(lint_amount("0.30000000000000004").is_some());
+/// assert!(lint_amount("1.0999999999999999").is_some());
+/// assert!(lint_amount("0.000001").is_none());
+/// assert!(lint_amount("100.25").is_none());
+/// ```
+pub fn lint_amount(amount: &str) -> Option<Lint> {
+    EXCESS_PRECISION_REGEX.is_match(amount).then(|| {
+        Lint::new(EXCESS_PRECISION_RULE, "The amount ends with digits which look like a floating point rounding error")
+    })
+}
+
+/// Look for the amounts more than a hundred times above or below the median amount of the campaign. The campaigns
+/// with fewer than ten recipients are not checked. The positions of the outliers are returned along with their
+/// findings.
+///
+/// # Examples
+///
+/// ```
+/// use ethers_core::types::U256;
+/// use sablier_merkle_api::utils::campaign_lints::amount_outliers;
+///
+/// let mut amounts: Vec<U256> = vec![1000.into(); 10];
+/// amounts[3] = 200000.into();
+/// amounts[7] = 5.into();
+/// let outliers: Vec<usize> = amount_outliers(&amounts).into_iter().map(|(position, _)| position).collect();
+/// assert_eq!(outliers, vec![3, 7]);
+/// ```
+pub fn amount_outliers(amounts: &[U256]) -> Vec<(usize, Lint)> {
+    if amounts.len() < MIN_OUTLIER_SAMPLE {
+        return Vec::new();
+    }
+
+    let mut sorted = amounts.to_vec();
+    sorted.sort_unstable();
+    let median = sorted[sorted.len() / 2];
+    let factor = U256::from(OUTLIER_FACTOR);
+
+    amounts
+        .iter()
+        .enumerate()
+        .filter_map(|(position, amount)| {
+            let direction = if median.checked_mul(factor).is_some_and(|limit| *amount > limit) {
+                "above"
+            } else if amount.checked_mul(factor).is_some_and(|scaled| scaled < median) {
+                "below"
+            } else {
+                return None;
+            };
+            let message = format!(
+                "The amount is more than {} times {} the median amount of the campaign",
+                OUTLIER_FACTOR, direction
+            );
+            Some((position, Lint { rule: AMOUNT_OUTLIER_RULE, message }))
+        })
+        .collect()
+}
@@ -0,0 +1,115 @@
+use dotenvy::dotenv;
+use ethers_core::types::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::csv_campaign_parser::pad_value;
+
+/// Name of the profile used when a request does not select one
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Limits enforced on the campaigns. The amounts are written like the amounts of the recipient list, so they are scaled
+/// by the same number of decimals.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CampaignRules {
+    pub min_recipients: usize,
+    pub max_recipients: Option<usize>,
+    pub min_amount: Option<String>,
+    pub max_amount: Option<String>,
+    pub max_total_amount: Option<String>,
+    /// Number of validation errors after which the parsing stops. Without it, every error is reported.
+    pub max_errors: Option<usize>,
+}
+
+impl Default for CampaignRules {
+    /// The rules applied to every campaign before the profiles were introduced
+    fn default() -> Self {
+        CampaignRules {
+            min_recipients: 2,
+            max_recipients: None,
+            min_amount: None,
+            max_amount: None,
+            max_total_amount: None,
+            max_errors: Some(100),
+        }
+    }
+}
+
+/// The amount limits of a rules profile, in base units
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AmountLimits {
+    pub min_amount: Option<U256>,
+    pub max_amount: Option<U256>,
+    pub max_total_amount: Option<U256>,
+}
+
+impl CampaignRules {
+    /// Get a profile out of a JSON object mapping the names of the profiles to their rules. The `default` profile falls
+    /// back on the default rules when it is not configured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::campaign_rules::CampaignRules;
+    ///
+    /// let profiles = r#"{ "acme": { "max_recipients": 1000, "max_amount": "5000" } }"#;
+    /// let rules = CampaignRules::from_profiles(profiles, "acme").unwrap();
+    /// assert_eq!(rules.max_recipients, Some(1000));
+    /// assert_eq!(rules.min_recipients, 2);
+    ///
+    /// assert_eq!(CampaignRules::from_profiles(profiles, "default"), Ok(CampaignRules::default()));
+    /// assert!(CampaignRules::from_profiles(profiles, "globex").is_err());
+    /// assert!(CampaignRules::from_profiles(r#"{ "default": { "min_recipients": 0 } }"#, "default").is_err());
+    /// ```
+    pub fn from_profiles(profiles: &str, name: &str) -> Result<CampaignRules, String> {
+        let mut profiles: HashMap<String, CampaignRules> =
+            serde_json::from_str(profiles).map_err(|e| format!("Invalid campaign rules profiles: {}", e))?;
+        let rules = match profiles.remove(name) {
+            Some(rules) => rules,
+            None if name == DEFAULT_PROFILE => CampaignRules::default(),
+            None => return Err(format!("Unknown campaign rules profile {}", name)),
+        };
+        if rules.min_recipients == 0 || rules.max_recipients.is_some_and(|max| max < rules.min_recipients) {
+            return Err(format!("The campaign rules profile {} should allow at least one recipient", name));
+        }
+        Ok(rules)
+    }
+
+    /// Get a profile out of the JSON object held by the `CAMPAIGN_RULES` environment variable. An empty name selects
+    /// the `default` profile.
+    pub fn from_env(name: &str) -> Result<CampaignRules, String> {
+        dotenv().ok();
+        let name = if name.trim().is_empty() { DEFAULT_PROFILE } else { name.trim() };
+        let profiles = std::env::var("CAMPAIGN_RULES").unwrap_or_else(|_| String::from("{}"));
+        CampaignRules::from_profiles(&profiles, name)
+    }
+
+    /// Get the amount limits in base units, given the number of decimals of the amounts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::campaign_rules::CampaignRules;
+    ///
+    /// let rules = CampaignRules { max_amount: Some(String::from("1.5")), ..Default::default() };
+    /// assert_eq!(rules.amount_limits(2).unwrap().max_amount, Some(150.into()));
+    /// assert!(rules.amount_limits(0).is_err());
+    /// ```
+    pub fn amount_limits(&self, decimals: usize) -> Result<AmountLimits, String> {
+        let limit = |name: &str, value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|value| {
+                    pad_value(value.trim(), decimals)
+                        .map_err(|e| format!("Invalid `{}` of the campaign rules {}: {}", name, value, e))
+                })
+                .transpose()
+        };
+        Ok(AmountLimits {
+            min_amount: limit("min_amount", &self.min_amount)?,
+            max_amount: limit("max_amount", &self.max_amount)?,
+            max_total_amount: limit("max_total_amount", &self.max_total_amount)?,
+        })
+    }
+}
@@ -0,0 +1,190 @@
+use csv::{Reader, ReaderBuilder};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use serde::Serialize;
+use std::io::{self, Cursor, Read};
+
+/// Number of bytes read ahead of the parsing to detect the dialect of a CSV file
+const SNIFF_LENGTH: u64 = 64 * 1024;
+
+/// Number of bytes inspected to recognize the UTF-16 files written without a byte order mark
+const UTF_16_SAMPLE_LENGTH: usize = 512;
+
+/// Delimiters that can separate the columns of a CSV file, by order of preference
+const DELIMITERS: [u8; 3] = [b',', b';', b'\t'];
+
+/// The way a CSV file is written, as detected from its first bytes
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CsvDialect {
+    /// Name of the text encoding of the file
+    pub encoding: String,
+    /// Whether the file starts with a byte order mark
+    pub bom: bool,
+    /// Character separating the columns
+    pub delimiter: char,
+}
+
+/// Detect the encoding of a file from its first bytes. The byte order mark is trusted when there is one. Otherwise,
+/// the text is read as UTF-16 when most of its odd or even bytes are zeros, as UTF-8 when it is valid UTF-8, and as
+/// Latin-1, in its `windows-1252` superset, as a last resort.
+fn detect_encoding(prefix: &[u8]) -> (&'static Encoding, bool) {
+    if let Some((encoding, _)) = Encoding::for_bom(prefix) {
+        return (encoding, true);
+    }
+
+    let sample = &prefix[..prefix.len().min(UTF_16_SAMPLE_LENGTH) & !1];
+    let zeros = |offset: usize| sample.iter().skip(offset).step_by(2).filter(|b| **b == 0).count();
+    if !sample.is_empty() {
+        if zeros(1) * 4 > sample.len() {
+            return (UTF_16LE, false);
+        }
+        if zeros(0) * 4 > sample.len() {
+            return (UTF_16BE, false);
+        }
+    }
+
+    match std::str::from_utf8(prefix) {
+        Ok(_) => (UTF_8, false),
+        // A character cut by the end of the prefix, when the file goes on after it
+        Err(error) if error.error_len().is_none() && prefix.len() as u64 == SNIFF_LENGTH => (UTF_8, false),
+        Err(_) => (WINDOWS_1252, false),
+    }
+}
+
+/// Detect the delimiter of a CSV file from its header, ignoring the quoted delimiters. The comma is used when the
+/// header holds none of the delimiters, or as many of several of them.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::csv_dialect::detect_delimiter;
+///
+/// assert_eq!(detect_delimiter("address;amount\n0x1,1"), b';');
+/// assert_eq!(detect_delimiter("address\tamount"), b'\t');
+/// assert_eq!(detect_delimiter("\"address;name\",amount"), b',');
+/// assert_eq!(detect_delimiter("address"), b',');
+/// ```
+pub fn detect_delimiter(text: &str) -> u8 {
+    let mut counts = [0; DELIMITERS.len()];
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '\r' | '\n' if !quoted => break,
+            _ if !quoted => {
+                if let Some(position) = DELIMITERS.iter().position(|d| *d as char == c) {
+                    counts[position] += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let best =
+        (0..DELIMITERS.len()).fold(0, |best, position| if counts[position] > counts[best] { position } else { best });
+    DELIMITERS[best]
+}
+
+/// Open a CSV file of any supported dialect. The start of the file is read ahead to detect its encoding and delimiter,
+/// then the file is transcoded to UTF-8, without its byte order mark, as it is read. The UTF-8 files are passed through
+/// as they are, so that the invalid bytes found after the detection prefix fail their rows instead of being silently
+/// replaced.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::csv_dialect::open_csv;
+///
+/// let data = b"\xEF\xBB\xBFaddress;amount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491;100";
+/// let (dialect, mut reader) = open_csv(&data[..]).unwrap();
+/// assert_eq!((dialect.encoding.as_str(), dialect.bom, dialect.delimiter), ("UTF-8", true, ';'));
+/// assert_eq!(reader.headers().unwrap(), vec!["address", "amount"]);
+/// ```
+pub fn open_csv<R: Read>(reader: R) -> io::Result<(CsvDialect, Reader<impl Read>)> {
+    let mut reader = reader;
+    let mut prefix = Vec::new();
+    reader.by_ref().take(SNIFF_LENGTH).read_to_end(&mut prefix)?;
+
+    let (encoding, bom) = detect_encoding(&prefix);
+    let delimiter = detect_delimiter(&encoding.decode_with_bom_removal(&prefix).0);
+    let dialect = CsvDialect { encoding: encoding.name().to_string(), bom, delimiter: delimiter as char };
+
+    let decoder = DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding).filter(|encoding| *encoding != UTF_8))
+        .strip_bom(true)
+        .utf8_passthru(true)
+        .build(Cursor::new(prefix).chain(reader));
+    Ok((dialect, ReaderBuilder::new().delimiter(delimiter).from_reader(decoder)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf_16(text: &str, little_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        for unit in units {
+            bytes.extend(if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() });
+        }
+        bytes
+    }
+
+    fn read_all(data: &[u8]) -> (CsvDialect, Vec<Vec<String>>) {
+        let (dialect, mut reader) = open_csv(data).unwrap();
+        let mut rows = vec![reader.headers().unwrap().iter().map(String::from).collect()];
+        rows.extend(reader.records().map(|r| r.unwrap().iter().map(String::from).collect()));
+        (dialect, rows)
+    }
+
+    #[test]
+    fn open_utf_16_files() {
+        let text = "address\tamount\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491\t100\n";
+        for (little_endian, bom) in [(true, true), (false, true), (true, false), (false, false)] {
+            let (dialect, rows) = read_all(&utf_16(text, little_endian, bom));
+
+            assert_eq!(dialect.encoding, if little_endian { "UTF-16LE" } else { "UTF-16BE" });
+            assert_eq!((dialect.bom, dialect.delimiter), (bom, '\t'));
+            assert_eq!(
+                rows,
+                vec![vec!["address", "amount"], vec!["0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", "100"]]
+            );
+        }
+    }
+
+    #[test]
+    fn open_latin_1_files() {
+        let (dialect, rows) = read_all(b"address,amount,name\n0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,Zo\xEB\n");
+
+        assert_eq!((dialect.encoding.as_str(), dialect.bom, dialect.delimiter), ("windows-1252", false, ','));
+        assert_eq!(rows[1][2], "Zoë");
+    }
+
+    #[test]
+    fn open_files_longer_than_the_sniffed_prefix() {
+        let row = "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491;100\n";
+        let text = format!("address;amount\n{}", row.repeat(2000));
+        let (dialect, rows) = read_all(text.as_bytes());
+
+        assert_eq!((dialect.encoding.as_str(), dialect.delimiter), ("UTF-8", ';'));
+        assert_eq!(rows.len(), 2001);
+    }
+
+    #[test]
+    fn invalid_bytes_after_the_sniffed_prefix_fail_their_row() {
+        let row = "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,Zoe\n";
+        for bom in [false, true] {
+            let mut data = if bom { b"\xEF\xBB\xBF".to_vec() } else { Vec::new() };
+            data.extend(format!("address,amount,name\n{}", row.repeat(2000)).into_bytes());
+            data.extend(b"0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491,100,Zo\xEB\n");
+            let (dialect, mut reader) = open_csv(&data[..]).unwrap();
+            let records: Vec<_> = reader.records().collect();
+
+            assert_eq!((dialect.encoding.as_str(), dialect.bom), ("UTF-8", bom));
+            assert_eq!(reader.headers().unwrap(), vec!["address", "amount", "name"]);
+            assert_eq!(records.len(), 2001);
+            assert!(records[..2000].iter().all(|record| record.is_ok()));
+            assert!(matches!(records[2000].as_ref().unwrap_err().kind(), csv::ErrorKind::Utf8 { .. }));
+        }
+    }
+}
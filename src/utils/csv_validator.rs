@@ -1,13 +1,64 @@
 use csv::StringRecord;
-use ethers_rs::Address;
+use ethers_rs::{Address, Eip55};
+use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::utils::{
+    leaf_schema::{LeafField, CLIFF_DURATION_FIELD, START_TIME_FIELD, TOTAL_DURATION_FIELD, TRANCHE_PERCENTAGES_FIELD},
+    merkle::encode_value,
+};
 
 /// Struct that encapsulates a validation error. It contains the row where the error occurred and the error message.
-#[derive(Serialize, Debug)]
+/// For the recipient lists provided as JSON or NDJSON, `index` holds the position of the faulty recipient inside the
+/// list and `row` counts the recipients from 1, with 0 standing for the list as a whole. The errors found in a cell
+/// also report its `column`, named the way spreadsheets do, and the errors of spreadsheets report their `sheet`. The
+/// same struct reports the warnings of the lint rules, which are named by their `rule`.
+#[derive(Serialize, Debug, Default)]
 pub struct ValidationError {
     pub row: usize,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sheet: Option<String>,
+}
+
+/// Get the spreadsheet name of a column from its zero based position.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::csv_validator::{column_name, column_position};
+///
+/// assert_eq!(column_name(0), "A");
+/// assert_eq!(column_name(25), "Z");
+/// assert_eq!(column_name(27), "AB");
+/// assert_eq!(column_position("AB"), Some(27));
+/// assert_eq!(column_position("a1"), None);
+/// ```
+pub fn column_name(position: usize) -> String {
+    let mut name = Vec::new();
+    let mut position = position + 1;
+    while position > 0 {
+        let letter = (position - 1) % 26;
+        name.push(b'A' + letter as u8);
+        position = (position - 1) / 26;
+    }
+    name.iter().rev().map(|&b| b as char).collect()
+}
+
+/// Get the zero based position of a column from its spreadsheet name, the reverse of `column_name`.
+pub fn column_position(name: &str) -> Option<usize> {
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    Some(name.bytes().fold(0, |position, b| position * 26 + (b - b'A') as usize + 1) - 1)
 }
 
 /// Checks if a string is a valid Ethereum address.
@@ -29,12 +80,38 @@ pub fn is_valid_eth_address(address: &str) -> bool {
     Address::try_from(address).is_ok()
 }
 
+/// Get the checksummed form of a mixed case address which does not match its EIP-55 checksum. The addresses written
+/// in a single case carry no checksum, so they never mismatch.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::csv_validator::checksum_mismatch;
+///
+/// let expected = "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491";
+/// assert_eq!(checksum_mismatch("0x9aD7CAD4F10D0c3f875b8a2fd292590490c9f491").as_deref(), Some(expected));
+/// assert_eq!(checksum_mismatch(expected), None);
+/// assert_eq!(checksum_mismatch("0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491"), None);
+/// assert_eq!(checksum_mismatch("0xthisIsNotAnAddress"), None);
+/// ```
+pub fn checksum_mismatch(address: &str) -> Option<String> {
+    let parsed = Address::from_str(address, false).ok()?;
+    let hex = address.trim_start_matches("0x");
+    let mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    let checksum = parsed.to_checksum_string();
+    (mixed_case && checksum[2..] != *hex).then_some(checksum)
+}
+
 /// Generic trait for a CSV column validator.
 pub trait ColumnValidator {
     /// Generic function that validates a CSV cell.
     fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError>;
     /// Generic function that validates a csv header.
     fn validate_header(&self, cel: &str) -> Option<ValidationError>;
+    /// Convert a valid CSV cell into the value used inside the merkle tree leaf.
+    fn leaf_value(&self, cel: &str) -> String {
+        cel.to_string()
+    }
 }
 
 /// Validator for a csv column that should contain valid Ethereum addresses
@@ -56,11 +133,14 @@ impl ColumnValidator for AddressColumnValidator {
     /// assert!(result_valid.is_none());
     /// assert!(!result_invalid.is_none());
     /// ```
-
     fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError> {
         let is_valid = is_valid_eth_address(cel);
         if !is_valid {
-            return Some(ValidationError { row: row_index + 2, message: String::from("Invalid Ethereum address") });
+            return Some(ValidationError {
+                row: row_index + 2,
+                message: String::from("Invalid Ethereum address"),
+                ..Default::default()
+            });
         }
         None
     }
@@ -86,12 +166,52 @@ impl ColumnValidator for AddressColumnValidator {
                 message: String::from(
                     "CSV header invalid. The csv header should be `address` column. The address column is missing",
                 ),
+                ..Default::default()
             });
         }
         None
     }
 }
 
+/// Validator for a csv column that should contain valid Ethereum addresses, whose mixed case addresses should also
+/// match their EIP-55 checksum. A wrong checksum in a hand edited file usually means a mistyped character.
+pub struct ChecksumAddressColumnValidator;
+
+impl ColumnValidator for ChecksumAddressColumnValidator {
+    /// Validate if a CSV cell contains a valid Ethereum address, with a valid checksum when written in mixed case
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ChecksumAddressColumnValidator, ColumnValidator};
+    /// let address_validator = ChecksumAddressColumnValidator;
+    ///
+    /// assert!(address_validator.validate_cel("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", 0).is_none());
+    /// assert!(address_validator.validate_cel("0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491", 0).is_none());
+    /// assert!(address_validator.validate_cel("0x9aD7CAD4F10D0c3f875b8a2fd292590490c9f491", 0).is_some());
+    /// assert!(address_validator.validate_cel("0xthisIsNotAnAddress", 0).is_some());
+    /// ```
+    fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError> {
+        if let Some(error) = AddressColumnValidator.validate_cel(cel, row_index) {
+            return Some(error);
+        }
+
+        checksum_mismatch(cel).map(|checksum| ValidationError {
+            row: row_index + 2,
+            message: format!(
+                "The address does not match its EIP-55 checksum and may hold a typo. Did you mean {}?",
+                checksum
+            ),
+            ..Default::default()
+        })
+    }
+
+    /// Validate if the csv header is valid
+    fn validate_header(&self, cel: &str) -> Option<ValidationError> {
+        AddressColumnValidator.validate_header(cel)
+    }
+}
+
 /// Validator for a csv column that should contain valid amount. The format of the amount is determined through the
 /// regex var.
 pub struct AmountColumnValidator {
@@ -129,14 +249,17 @@ impl ColumnValidator for AmountColumnValidator {
         if !is_valid {
             return Some(ValidationError {
                 row: row_index + 2,
-                message: String::from("Amounts should be positive, in normal notation, with an optional decimal point and a maximum number of decimals as provided by the query parameter."),
+                message: String::from("Amounts should be positive, in normal notation, with an optional decimal point and a maximum number of decimals as provided by the query parameter."), ..Default::default()
             });
         }
 
-        let amount: f64 = cel.parse().unwrap();
-
-        if amount == 0.0 {
-            return Some(ValidationError { row: row_index + 2, message: String::from("The amount cannot be 0") });
+        // The regex only lets digits through, so the amount is zero when none of them is significant
+        if !cel.chars().any(|c| ('1'..='9').contains(&c)) {
+            return Some(ValidationError {
+                row: row_index + 2,
+                message: String::from("The amount cannot be 0"),
+                ..Default::default()
+            });
         }
         None
     }
@@ -158,23 +281,331 @@ impl ColumnValidator for AmountColumnValidator {
     /// assert!(!result_invalid.is_none());
     /// ```
     fn validate_header(&self, cel: &str) -> Option<ValidationError> {
-        if cel.to_lowercase() != "amount" {
+        validate_amount_header(cel)
+    }
+}
+
+/// Validate the header of the amount column
+fn validate_amount_header(cel: &str) -> Option<ValidationError> {
+    if cel.to_lowercase() != "amount" {
+        return Some(ValidationError {
+            row: 1, // Header is in the first row
+            message: String::from(
+                "CSV header invalid. The csv header should contain `amount` column. The amount column id missing",
+            ),
+            ..Default::default()
+        });
+    }
+    None
+}
+
+/// Validator for a csv column that should contain raw amounts, i.e. exact integers expressed in the base units of the
+/// token
+pub struct RawAmountColumnValidator;
+
+impl ColumnValidator for RawAmountColumnValidator {
+    /// Validate if a CSV cell contains a valid raw amount
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ColumnValidator, RawAmountColumnValidator};
+    ///
+    /// let amount_validator = RawAmountColumnValidator;
+    ///
+    /// assert!(amount_validator.validate_cel("1000000000000000000", 0).is_none());
+    /// assert!(amount_validator.validate_cel("1.5", 0).is_some());
+    /// assert!(amount_validator.validate_cel("+1", 0).is_some());
+    /// assert!(amount_validator.validate_cel("0", 0).is_some());
+    /// ```
+    fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError> {
+        if cel.is_empty() || !cel.chars().all(|c| c.is_ascii_digit()) {
             return Some(ValidationError {
-                row: 1, // Header is in the first row
+                row: row_index + 2,
                 message: String::from(
-                    "CSV header invalid. The csv header should contain `amount` column. The amount column id missing",
+                    "Raw amounts should be integers in base units, without a sign, a decimal point or an exponent.",
                 ),
+                ..Default::default()
+            });
+        }
+
+        if cel.chars().all(|c| c == '0') {
+            return Some(ValidationError {
+                row: row_index + 2,
+                message: String::from("The amount cannot be 0"),
+                ..Default::default()
             });
         }
         None
     }
+
+    /// Validate if the csv header is valid
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ColumnValidator, RawAmountColumnValidator};
+    ///
+    /// assert!(RawAmountColumnValidator.validate_header("amount").is_none());
+    /// assert!(RawAmountColumnValidator.validate_header("address").is_some());
+    /// ```
+    fn validate_header(&self, cel: &str) -> Option<ValidationError> {
+        validate_amount_header(cel)
+    }
+}
+
+/// Validator for an additional column of the merkle tree leaves. The cells should be valid values of the Solidity type
+/// declared for the column by the leaf schema of the campaign.
+pub struct LeafColumnValidator {
+    pub name: String,
+    pub kind: String,
+}
+
+impl ColumnValidator for LeafColumnValidator {
+    /// Validate if a CSV cell contains a value of the column type
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ColumnValidator, LeafColumnValidator};
+    ///
+    /// let validator = LeafColumnValidator { name: String::from("start_time"), kind: String::from("uint40") };
+    ///
+    /// assert!(validator.validate_cel("1700000000", 0).is_none());
+    /// assert!(validator.validate_cel("-1", 0).is_some());
+    /// assert!(validator.validate_cel("2000000000000", 0).is_some());
+    /// ```
+    fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError> {
+        encode_value(cel, &self.kind).err().map(|error| ValidationError {
+            row: row_index + 2,
+            message: format!("Invalid `{}` value: {}", self.name, error),
+            ..Default::default()
+        })
+    }
+
+    /// Validate if the csv header is valid
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ColumnValidator, LeafColumnValidator};
+    ///
+    /// let validator = LeafColumnValidator { name: String::from("start_time"), kind: String::from("uint40") };
+    ///
+    /// assert!(validator.validate_header("Start_Time").is_none());
+    /// assert!(validator.validate_header("amount").is_some());
+    /// ```
+    fn validate_header(&self, cel: &str) -> Option<ValidationError> {
+        validate_leaf_column_header(cel, &self.name)
+    }
+}
+
+/// Largest value of a `uint40`, the type of the timestamps and durations of the Sablier streams
+const MAX_UINT40: u64 = (1 << 40) - 1;
+
+/// Number of decimals of the `UD2x18` percentages, where `10^18` stands for 100%
+const PERCENTAGE_DECIMALS: usize = 18;
+
+/// A percentage with at most 16 decimals, so that it can be written as a `UD2x18` number
+static PERCENTAGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d{1,3}(\.\d{1,16})?$").unwrap());
+
+/// Validator for a csv column that should contain Unix timestamps, in seconds. A zero timestamp is accepted, as the
+/// Merkle Lockup contracts then start the stream at the time of the claim.
+pub struct TimestampColumnValidator {
+    pub name: String,
+}
+
+impl ColumnValidator for TimestampColumnValidator {
+    /// Validate if a CSV cell contains a valid Unix timestamp
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ColumnValidator, TimestampColumnValidator};
+    ///
+    /// let validator = TimestampColumnValidator { name: String::from("start_time") };
+    ///
+    /// assert!(validator.validate_cel("1700000000", 0).is_none());
+    /// assert!(validator.validate_cel("2023-11-14", 0).is_some());
+    /// assert!(validator.validate_cel("1700000000000000", 0).is_some());
+    /// ```
+    fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError> {
+        match parse_seconds(cel) {
+            Some(_) => None,
+            None => Some(ValidationError {
+                row: row_index + 2,
+                message: format!("The `{}` value should be a Unix timestamp, in seconds", self.name),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn validate_header(&self, cel: &str) -> Option<ValidationError> {
+        validate_leaf_column_header(cel, &self.name)
+    }
+}
+
+/// Validator for a csv column that should contain durations, in seconds
+pub struct DurationColumnValidator {
+    pub name: String,
+    pub allow_zero: bool,
+}
+
+impl ColumnValidator for DurationColumnValidator {
+    /// Validate if a CSV cell contains a valid duration
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ColumnValidator, DurationColumnValidator};
+    ///
+    /// let validator = DurationColumnValidator { name: String::from("total_duration"), allow_zero: false };
+    ///
+    /// assert!(validator.validate_cel("31536000", 0).is_none());
+    /// assert!(validator.validate_cel("0", 0).is_some());
+    /// assert!(validator.validate_cel("1.5", 0).is_some());
+    /// ```
+    fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError> {
+        match parse_seconds(cel) {
+            Some(0) if !self.allow_zero => Some(ValidationError {
+                row: row_index + 2,
+                message: format!("The `{}` value cannot be 0", self.name),
+                ..Default::default()
+            }),
+            Some(_) => None,
+            None => Some(ValidationError {
+                row: row_index + 2,
+                message: format!("The `{}` value should be a whole number of seconds", self.name),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn validate_header(&self, cel: &str) -> Option<ValidationError> {
+        validate_leaf_column_header(cel, &self.name)
+    }
+}
+
+/// Validator for a csv column that should contain the unlock percentages of the tranches of a stream, separated by
+/// `;`. The percentages should be positive and add up to 100. Inside the leaf, they become an array of `UD2x18`
+/// numbers.
+pub struct PercentagesColumnValidator {
+    pub name: String,
+}
+
+impl ColumnValidator for PercentagesColumnValidator {
+    /// Validate if a CSV cell contains valid tranche percentages
+    ///
+    ///  # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::{ColumnValidator, PercentagesColumnValidator};
+    ///
+    /// let validator = PercentagesColumnValidator { name: String::from("tranche_percentages") };
+    ///
+    /// assert!(validator.validate_cel("25;25;50", 0).is_none());
+    /// assert!(validator.validate_cel("33.5;66.5", 0).is_none());
+    /// assert!(validator.validate_cel("25;25", 0).is_some());
+    /// assert!(validator.validate_cel("0;100", 0).is_some());
+    /// assert_eq!(validator.leaf_value("25;75"), "[250000000000000000,750000000000000000]");
+    /// ```
+    fn validate_cel(&self, cel: &str, row_index: usize) -> Option<ValidationError> {
+        let percentages = parse_percentages(cel);
+        let message = match percentages {
+            None => format!(
+                "The `{}` value should contain positive percentages separated by `;`, e.g. `25;25;50`",
+                self.name
+            ),
+            Some(percentages) if percentages.iter().sum::<u128>() != 10u128.pow(PERCENTAGE_DECIMALS as u32) => {
+                format!("The `{}` percentages should add up to 100", self.name)
+            }
+            Some(_) => return None,
+        };
+        Some(ValidationError { row: row_index + 2, message, ..Default::default() })
+    }
+
+    fn validate_header(&self, cel: &str) -> Option<ValidationError> {
+        validate_leaf_column_header(cel, &self.name)
+    }
+
+    fn leaf_value(&self, cel: &str) -> String {
+        let percentages = parse_percentages(cel).unwrap_or_default();
+        let items: Vec<String> = percentages.iter().map(|p| p.to_string()).collect();
+        format!("[{}]", items.join(","))
+    }
+}
+
+/// Parse a whole number of seconds that fits into a `uint40`
+fn parse_seconds(cel: &str) -> Option<u64> {
+    if cel.is_empty() || !cel.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    cel.parse::<u64>().ok().filter(|seconds| *seconds <= MAX_UINT40)
+}
+
+/// Parse `;` separated positive percentages into `UD2x18` numbers
+fn parse_percentages(cel: &str) -> Option<Vec<u128>> {
+    let mut percentages = Vec::new();
+    for percentage in cel.split(';').map(str::trim) {
+        if !PERCENTAGE_REGEX.is_match(percentage) {
+            return None;
+        }
+        let (whole, fraction) = percentage.split_once('.').unwrap_or((percentage, ""));
+        let digits = format!("{}{}{}", whole, fraction, "0".repeat(PERCENTAGE_DECIMALS - 2 - fraction.len()));
+        let value: u128 = digits.parse().ok()?;
+        if value == 0 {
+            return None;
+        }
+        percentages.push(value);
+    }
+    Some(percentages)
+}
+
+/// Validate the header of a column required by the leaf schema
+fn validate_leaf_column_header(cel: &str, name: &str) -> Option<ValidationError> {
+    if cel.to_lowercase() != name {
+        return Some(ValidationError {
+            row: 1, // Header is in the first row
+            message: format!(
+                "CSV header invalid. The csv header should contain `{}` column, as required by the leaf schema",
+                name
+            ),
+            ..Default::default()
+        });
+    }
+    None
+}
+
+/// Get the validator of a column required by the leaf schema. The stream fields have dedicated validators, while the
+/// other fields are validated against their type.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::{csv_validator::leaf_column_validator, leaf_schema::LeafField};
+///
+/// let field = LeafField { name: String::from("cliff_duration"), kind: String::from("uint40") };
+/// assert!(leaf_column_validator(&field).validate_cel("0", 0).is_none());
+///
+/// let field = LeafField { name: String::from("total_duration"), kind: String::from("uint40") };
+/// assert!(leaf_column_validator(&field).validate_cel("0", 0).is_some());
+/// ```
+pub fn leaf_column_validator(field: &LeafField) -> Box<dyn ColumnValidator> {
+    let name = field.name.clone();
+    match field.name.as_str() {
+        START_TIME_FIELD => Box::new(TimestampColumnValidator { name }),
+        CLIFF_DURATION_FIELD => Box::new(DurationColumnValidator { name, allow_zero: true }),
+        TOTAL_DURATION_FIELD => Box::new(DurationColumnValidator { name, allow_zero: false }),
+        TRANCHE_PERCENTAGES_FIELD => Box::new(PercentagesColumnValidator { name }),
+        _ => Box::new(LeafColumnValidator { name, kind: field.kind.clone() }),
+    }
 }
 
 /// Validates a full CSV row based on an array of objects that implement the ColumnValidator trait.
 ///
 ///  # Examples
 /// ```
-/// 
+///
 /// use sablier_merkle_api::utils::csv_validator::{AddressColumnValidator ,AmountColumnValidator, ColumnValidator,validate_csv_row};
 /// use regex::Regex;
 /// use csv::StringRecord;
@@ -204,6 +635,7 @@ pub fn validate_csv_row(
         errors.push(ValidationError {
             row: row_index + 2, // +2 to account for CSV header
             message: String::from("Insufficient columns"),
+            ..Default::default()
         });
         return errors;
     }
@@ -211,7 +643,7 @@ pub fn validate_csv_row(
         let cel = row[index].trim();
         let cel_error = validator.validate_cel(cel, row_index);
         if let Some(error) = cel_error {
-            errors.push(error);
+            errors.push(ValidationError { column: Some(column_name(index)), ..error });
         }
     }
     errors
@@ -221,7 +653,7 @@ pub fn validate_csv_row(
 ///
 ///  # Examples
 /// ```
-/// 
+///
 /// use sablier_merkle_api::utils::csv_validator::{AddressColumnValidator ,AmountColumnValidator, ColumnValidator,validate_csv_header};
 /// use regex::Regex;
 /// use csv::StringRecord;
@@ -239,19 +671,138 @@ pub fn validate_csv_row(
 ///  ```
 pub fn validate_csv_header(header: &StringRecord, validators: &[&dyn ColumnValidator]) -> Option<ValidationError> {
     if header.len() < validators.len() {
-        let error = ValidationError { row: 1, message: String::from("Insufficient columns") };
+        let error = ValidationError { row: 1, message: String::from("Insufficient columns"), ..Default::default() };
         return Some(error);
     }
     for (index, validator) in validators.iter().enumerate() {
         let head = header[index].trim();
         let header_error = validator.validate_header(head);
         if let Some(error) = header_error {
-            return Some(error);
+            return Some(ValidationError { column: Some(column_name(index)), ..error });
         }
     }
     None
 }
 
+/// Alternative names of the CSV columns, mapping each lowercase alias to the name of the column it stands for
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnAliases {
+    aliases: HashMap<String, String>,
+}
+
+impl Default for ColumnAliases {
+    /// The names given to the recipient columns by the usual exports
+    fn default() -> Self {
+        let aliases = [("wallet", "address"), ("recipient", "address"), ("account", "address"), ("value", "amount")];
+        ColumnAliases { aliases: aliases.iter().map(|(alias, name)| (alias.to_string(), name.to_string())).collect() }
+    }
+}
+
+impl ColumnAliases {
+    /// Parse a comma separated list of `alias:column` pairs, added to the default aliases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::csv_validator::ColumnAliases;
+    ///
+    /// let aliases = ColumnAliases::parse("Payout:amount, to:address").unwrap();
+    /// assert_eq!(aliases.resolve(" PAYOUT "), "amount");
+    /// assert_eq!(aliases.resolve("Wallet"), "address");
+    /// assert_eq!(aliases.resolve("Name"), "name");
+    /// assert!(ColumnAliases::parse("payout").is_err());
+    /// ```
+    pub fn parse(spec: &str) -> Result<ColumnAliases, String> {
+        let mut aliases = ColumnAliases::default();
+        for entry in spec.split(',').filter(|entry| !entry.trim().is_empty()) {
+            let (alias, name) = entry
+                .split_once(':')
+                .map(|(alias, name)| (alias.trim().to_lowercase(), name.trim().to_lowercase()))
+                .filter(|(alias, name)| !alias.is_empty() && !name.is_empty())
+                .ok_or_else(|| {
+                    format!("The column alias {} should be written `alias:column`, e.g. `wallet:address`", entry)
+                })?;
+            aliases.aliases.insert(alias, name);
+        }
+        Ok(aliases)
+    }
+
+    /// Get the name of the column a header cell stands for
+    pub fn resolve(&self, cel: &str) -> String {
+        let name = cel.trim().to_lowercase();
+        self.aliases.get(&name).cloned().unwrap_or(name)
+    }
+}
+
+/// Locates the columns of the validators inside a CSV header, from their names or aliases. The columns can come in any
+/// order and the unknown columns are ignored. A column is looked up by its alias only when no column bears its name,
+/// and the columns named after a validator are never taken as the alias of another one. Returns the position of the
+/// column of each validator.
+///
+///  # Examples
+/// ```
+/// use sablier_merkle_api::utils::csv_validator::{
+///     map_csv_header, AddressColumnValidator, ColumnAliases, ColumnValidator, RawAmountColumnValidator,
+/// };
+/// use csv::StringRecord;
+///
+/// let validators: Vec<&dyn ColumnValidator> = vec![&AddressColumnValidator, &RawAmountColumnValidator];
+/// let aliases = ColumnAliases::default();
+/// let header = StringRecord::from(vec!["name", "Value", "Wallet"]);
+/// assert_eq!(map_csv_header(&header, &validators, &aliases).unwrap(), vec![2, 1]);
+/// let header = StringRecord::from(vec!["address", "amount", "value", "wallet"]);
+/// assert_eq!(map_csv_header(&header, &validators, &aliases).unwrap(), vec![0, 1]);
+/// let header = StringRecord::from(vec!["wallet", "account", "amount"]);
+/// assert!(map_csv_header(&header, &validators, &aliases).is_err());
+/// let header = StringRecord::from(vec!["address", "total"]);
+/// assert!(map_csv_header(&header, &validators, &aliases).is_err());
+///  ```
+pub fn map_csv_header(
+    header: &StringRecord,
+    validators: &[&dyn ColumnValidator],
+    aliases: &ColumnAliases,
+) -> Result<Vec<usize>, ValidationError> {
+    if header.len() < validators.len() {
+        return Err(ValidationError { row: 1, message: String::from("Insufficient columns"), ..Default::default() });
+    }
+
+    let names: Vec<String> = header.iter().map(|cel| cel.trim().to_lowercase()).collect();
+    let resolved: Vec<String> = header.iter().map(|cel| aliases.resolve(cel)).collect();
+    let named = |validator: &&dyn ColumnValidator| -> Vec<usize> {
+        (0..names.len()).filter(|&position| validator.validate_header(&names[position]).is_none()).collect()
+    };
+    let taken: Vec<usize> = validators.iter().flat_map(named).collect();
+
+    let mut columns = Vec::new();
+    for validator in validators {
+        let mut matching = named(validator);
+        if matching.is_empty() {
+            matching = (0..names.len())
+                .filter(|position| !taken.contains(position) && resolved[*position] != names[*position])
+                .filter(|&position| validator.validate_header(&resolved[position]).is_none())
+                .collect();
+        }
+        match matching[..] {
+            [column] => columns.push(column),
+            [first, second, ..] => {
+                return Err(ValidationError {
+                    row: 1,
+                    message: format!(
+                        "CSV header invalid. The columns {} and {} both stand for `{}`, keep only one of them",
+                        column_name(first),
+                        column_name(second),
+                        resolved[first]
+                    ),
+                    column: Some(column_name(second)),
+                    ..Default::default()
+                })
+            }
+            [] => return Err(validator.validate_header("").unwrap_or_default()),
+        }
+    }
+    Ok(columns)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
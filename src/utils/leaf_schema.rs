@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::utils::merkle::is_supported_type;
+
+/// Name of the leaf field holding the index of the recipient
+pub const INDEX_FIELD: &str = "index";
+
+/// Name of the leaf field holding the address of the recipient
+pub const ADDRESS_FIELD: &str = "address";
+
+/// Name of the leaf field holding the amount of the recipient
+pub const AMOUNT_FIELD: &str = "amount";
+
+/// Name of the leaf field holding the start time of the stream of the recipient, as a Unix timestamp
+pub const START_TIME_FIELD: &str = "start_time";
+
+/// Name of the leaf field holding the cliff duration of the stream of the recipient, in seconds
+pub const CLIFF_DURATION_FIELD: &str = "cliff_duration";
+
+/// Name of the leaf field holding the total duration of the stream of the recipient, in seconds
+pub const TOTAL_DURATION_FIELD: &str = "total_duration";
+
+/// Name of the leaf field holding the unlock percentages of the tranches of the stream of the recipient
+pub const TRANCHE_PERCENTAGES_FIELD: &str = "tranche_percentages";
+
+/// Get the type of a stream field, used when the leaf schema names the field without a type. The timestamps and
+/// durations are `uint40` and the percentages are `UD2x18` numbers, i.e. `uint64` with 18 decimals.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::leaf_schema::stream_field_type;
+///
+/// assert_eq!(stream_field_type("cliff_duration"), Some("uint40"));
+/// assert_eq!(stream_field_type("tranche_percentages"), Some("uint64[]"));
+/// assert_eq!(stream_field_type("amount"), None);
+/// ```
+pub fn stream_field_type(name: &str) -> Option<&'static str> {
+    match name {
+        START_TIME_FIELD | CLIFF_DURATION_FIELD | TOTAL_DURATION_FIELD => Some("uint40"),
+        TRANCHE_PERCENTAGES_FIELD => Some("uint64[]"),
+        _ => None,
+    }
+}
+
+/// A named and typed field of a merkle tree leaf
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct LeafField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// The ordered fields that make up the leaves of a campaign. The `index`, `address` and `amount` fields are filled
+/// from the recipient itself, while any other field is read from the CSV column with the same name.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(transparent)]
+pub struct LeafSchema {
+    pub fields: Vec<LeafField>,
+}
+
+impl Default for LeafSchema {
+    /// The `[index, address, amount]` leaf used by the original Sablier Merkle contracts
+    fn default() -> Self {
+        LeafSchema::of(&[(INDEX_FIELD, "uint"), (ADDRESS_FIELD, "address"), (AMOUNT_FIELD, "uint256")])
+    }
+}
+
+impl LeafSchema {
+    fn of(fields: &[(&str, &str)]) -> Self {
+        LeafSchema {
+            fields: fields
+                .iter()
+                .map(|(name, kind)| LeafField { name: name.to_string(), kind: kind.to_string() })
+                .collect(),
+        }
+    }
+
+    /// Parse a leaf schema from a comma separated list of `name:type` fields. The names can be omitted for the first
+    /// three fields, which then stand for the index, the address and the amount, and the types can be omitted for the
+    /// stream fields. An empty specification results in the default schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::leaf_schema::LeafSchema;
+    ///
+    /// let schema = LeafSchema::parse("uint256,address,uint128").unwrap();
+    /// assert_eq!(schema.types(), vec!["uint256", "address", "uint128"]);
+    ///
+    /// let schema = LeafSchema::parse("index:uint256, address:address, amount:uint128, start_time:uint40").unwrap();
+    /// assert_eq!(schema.extra_fields()[0].name, "start_time");
+    ///
+    /// let schema = LeafSchema::parse("uint256,address,uint128,start_time,total_duration").unwrap();
+    /// assert_eq!(schema.types(), vec!["uint256", "address", "uint128", "uint40", "uint40"]);
+    ///
+    /// assert_eq!(LeafSchema::parse("").unwrap(), LeafSchema::default());
+    /// assert!(LeafSchema::parse("uint256,uint128").is_err());
+    /// ```
+    pub fn parse(spec: &str) -> Result<LeafSchema, String> {
+        if spec.trim().is_empty() {
+            return Ok(LeafSchema::default());
+        }
+
+        let default_names = [INDEX_FIELD, ADDRESS_FIELD, AMOUNT_FIELD];
+        let mut fields = Vec::new();
+        for (position, entry) in spec.split(',').enumerate() {
+            let entry = entry.trim().to_lowercase();
+            let (name, kind) = match (entry.split_once(':'), stream_field_type(&entry)) {
+                (Some((name, kind)), _) => (name.trim().to_string(), kind.trim().to_string()),
+                (None, Some(kind)) => (entry.clone(), kind.to_string()),
+                (None, None) => match default_names.get(position) {
+                    Some(name) => (name.to_string(), entry),
+                    None => return Err(format!("The leaf field {} should be named, e.g. `vesting_id:uint256`", entry)),
+                },
+            };
+            fields.push(LeafField { name, kind });
+        }
+
+        let schema = LeafSchema { fields };
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    /// Check that the fields are unique, supported, and that the recipient fields have suitable types.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+        for field in &self.fields {
+            if field.name.is_empty() || !names.insert(field.name.as_str()) {
+                return Err(format!("Invalid or duplicated leaf field name `{}`", field.name));
+            }
+            if !is_supported_type(&field.kind) {
+                return Err(format!("Unsupported leaf type {}", field.kind));
+            }
+            let is_valid = match field.name.as_str() {
+                ADDRESS_FIELD => field.kind == "address",
+                INDEX_FIELD | AMOUNT_FIELD => field.kind.starts_with("uint"),
+                _ => true,
+            };
+            if !is_valid {
+                return Err(format!("The leaf field `{}` cannot be of type {}", field.name, field.kind));
+            }
+        }
+
+        for name in [ADDRESS_FIELD, AMOUNT_FIELD] {
+            if !names.contains(name) {
+                return Err(format!("The leaf schema should contain the `{}` field", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the types of the fields, i.e. the `leaf_encoding` of the merkle tree
+    pub fn types(&self) -> Vec<String> {
+        self.fields.iter().map(|f| f.kind.clone()).collect()
+    }
+
+    /// Get the fields that are not filled from the recipient itself, in order
+    pub fn extra_fields(&self) -> Vec<&LeafField> {
+        self.fields.iter().filter(|f| ![INDEX_FIELD, ADDRESS_FIELD, AMOUNT_FIELD].contains(&f.name.as_str())).collect()
+    }
+
+    /// Build the values of a leaf. The `extra` values follow the order of `extra_fields`.
+    pub fn leaf_values(&self, index: usize, address: &str, amount: &str, extra: &[String]) -> Vec<String> {
+        let mut extra = extra.iter();
+        self.fields
+            .iter()
+            .map(|field| match field.name.as_str() {
+                INDEX_FIELD => index.to_string(),
+                ADDRESS_FIELD => address.to_string(),
+                AMOUNT_FIELD => amount.to_string(),
+                _ => extra.next().cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_invalid_schemas() {
+        assert!(LeafSchema::parse("uint256,address,uint128,uint40").is_err());
+        assert!(LeafSchema::parse("uint256,uint128,address").is_err());
+        assert!(LeafSchema::parse("index:uint256,address:address,amount:uint128,amount:uint40").is_err());
+        assert!(LeafSchema::parse("address:address,amount:uint7").is_err());
+        assert!(LeafSchema::parse("index:uint256,address:address").is_err());
+    }
+
+    #[test]
+    fn leaf_values_follow_the_schema() {
+        let schema = LeafSchema::parse("address:address,start_time:uint40,amount:uint128,index:uint256").unwrap();
+        let values =
+            schema.leaf_values(3, "0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491", "100", &["1700000000".to_string()]);
+
+        assert_eq!(values, vec!["0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491", "1700000000", "100", "3"]);
+    }
+}
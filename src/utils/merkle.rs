@@ -0,0 +1,523 @@
+use ethers_core::{
+    abi::{self, Token},
+    types::{Address, I256, U256},
+    utils::keccak256,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A value of an OpenZeppelin `standard-v1` merkle tree together with the position of its leaf inside the tree
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MerkleTreeValue {
+    pub value: Vec<String>,
+    pub tree_index: usize,
+}
+
+/// Mirror of the OpenZeppelin `standard-v1` merkle tree dump, as stored inside the campaign files
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MerkleTreeDump {
+    pub format: String,
+    pub tree: Vec<String>,
+    pub values: Vec<MerkleTreeValue>,
+    pub leaf_encoding: Vec<String>,
+}
+
+/// A proof for several leaves at once, compatible with OpenZeppelin `MerkleProof.multiProofVerify`
+#[derive(Debug, PartialEq)]
+pub struct MultiProof {
+    pub leaves: Vec<Vec<String>>,
+    pub proof: Vec<String>,
+    pub proof_flags: Vec<bool>,
+}
+
+impl MerkleTreeDump {
+    /// Build a `standard-v1` merkle tree of the values, the same way OpenZeppelin `StandardMerkleTree.of` does: the
+    /// leaves are sorted by hash and laid out from the end of the tree. On failure, the index of the offending value
+    /// is returned together with the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::merkle::MerkleTreeDump;
+    ///
+    /// let values = vec![
+    ///     vec!["0".to_string(), "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491".to_string(), "100".to_string()],
+    ///     vec!["1".to_string(), "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc".to_string(), "200".to_string()],
+    /// ];
+    /// let types = vec!["uint256".to_string(), "address".to_string(), "uint128".to_string()];
+    /// let dump = MerkleTreeDump::build(values, types).unwrap();
+    /// assert_eq!(dump.tree.len(), 3);
+    /// assert_eq!(dump.proof(0).unwrap(), vec![dump.tree[dump.values[1].tree_index].clone()]);
+    /// ```
+    pub fn build(values: Vec<Vec<String>>, leaf_encoding: Vec<String>) -> Result<MerkleTreeDump, (usize, String)> {
+        if values.is_empty() {
+            return Err((0, String::from("Expected a non-zero number of leaves")));
+        }
+
+        let mut hashed_values = Vec::with_capacity(values.len());
+        for (value_index, value) in values.iter().enumerate() {
+            hashed_values.push((leaf_hash(value, &leaf_encoding).map_err(|e| (value_index, e))?, value_index));
+        }
+        hashed_values.sort_by_key(|a| a.0);
+
+        let length = 2 * hashed_values.len() - 1;
+        let mut tree = vec![[0u8; 32]; length];
+        let mut tree_indexes = vec![0; values.len()];
+        for (leaf_index, (hash, value_index)) in hashed_values.iter().enumerate() {
+            tree[length - 1 - leaf_index] = *hash;
+            tree_indexes[*value_index] = length - 1 - leaf_index;
+        }
+        for i in (0..length - hashed_values.len()).rev() {
+            tree[i] = hash_pair(&tree[2 * i + 1], &tree[2 * i + 2]);
+        }
+
+        Ok(MerkleTreeDump {
+            format: String::from("standard-v1"),
+            tree: tree.iter().map(|node| encode_node(node)).collect(),
+            values: values
+                .into_iter()
+                .zip(tree_indexes)
+                .map(|(value, tree_index)| MerkleTreeValue { value, tree_index })
+                .collect(),
+            leaf_encoding,
+        })
+    }
+
+    /// Get the root of the tree
+    pub fn root(&self) -> String {
+        self.tree.first().cloned().unwrap_or_default()
+    }
+
+    /// Get the depth of the tree, i.e. the length of the longest proof
+    pub fn depth(&self) -> usize {
+        self.tree.len().checked_ilog2().unwrap_or_default() as usize
+    }
+
+    /// Build the proof of the value found at the specified index, following `StandardMerkleTree.getProof`.
+    pub fn proof(&self, value_index: usize) -> Result<Vec<String>, String> {
+        let value = self.values.get(value_index).ok_or_else(|| format!("Index {} is out of range", value_index))?;
+        if value.tree_index >= self.tree.len() {
+            return Err(format!("Invalid tree index {}", value.tree_index));
+        }
+
+        let mut proof = Vec::new();
+        let mut index = value.tree_index;
+        while index > 0 {
+            proof.push(self.tree[sibling_index(index)].clone());
+            index = (index - 1) / 2;
+        }
+        Ok(proof)
+    }
+
+    /// Check that the dump is a well formed `standard-v1` tree whose leaf types can be encoded.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.format != "standard-v1" || self.tree.is_empty() || self.tree.len().is_multiple_of(2) {
+            return Err(String::from("Invalid merkle tree: unknown format"));
+        }
+        for node in &self.tree {
+            decode_node(node)?;
+        }
+        if let Some(value) = self.values.iter().find(|v| v.tree_index >= self.tree.len()) {
+            return Err(format!("Invalid merkle tree: tree index {} is out of range", value.tree_index));
+        }
+        if let Some(kind) = self.leaf_encoding.iter().find(|kind| !is_supported_type(kind)) {
+            return Err(format!("Invalid merkle tree: unsupported leaf type {}", kind));
+        }
+        Ok(())
+    }
+
+    /// Build the multiproof for the values found at the specified indexes. The leaves are returned in the order
+    /// expected by `MerkleProof.multiProofVerify`. Like OpenZeppelin's `getMultiProof`, it rejects the duplicated
+    /// indexes, which would leave the caller with fewer leaves than requested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use merkle_tree_rs::standard::StandardMerkleTree;
+    /// use sablier_merkle_api::utils::merkle::MerkleTreeDump;
+    ///
+    /// let values = vec![
+    ///     vec!["0".to_string(), "0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491".to_string(), "100".to_string()],
+    ///     vec!["1".to_string(), "0xf976aF93B0A5A9F55A7f285a3B5355B8575Eb5bc".to_string(), "200".to_string()],
+    /// ];
+    /// let tree = StandardMerkleTree::of(values, &["uint".to_string(), "address".to_string(), "uint256".to_string()]);
+    /// let dump: MerkleTreeDump = serde_json::from_value(serde_json::json!(tree.dump())).unwrap();
+    ///
+    /// let multi_proof = dump.multi_proof(&[0, 1]).unwrap();
+    /// assert_eq!(multi_proof.leaves.len(), 2);
+    /// assert!(multi_proof.proof.is_empty());
+    /// assert_eq!(multi_proof.proof_flags, vec![true]);
+    /// ```
+    pub fn multi_proof(&self, value_indexes: &[usize]) -> Result<MultiProof, String> {
+        let mut leaves_by_tree_index = BTreeMap::new();
+        for index in value_indexes {
+            let value = self.values.get(*index).ok_or_else(|| format!("Index {} is out of range", index))?;
+            if leaves_by_tree_index.insert(value.tree_index, *index).is_some() {
+                return Err(format!("Cannot prove duplicated index {}", index));
+            }
+        }
+
+        // Process the leaves from the deepest to the shallowest
+        let tree_indexes: Vec<usize> = leaves_by_tree_index.keys().rev().copied().collect();
+
+        let mut stack = tree_indexes.clone();
+        let mut position = 0;
+        let mut proof = Vec::new();
+        let mut proof_flags = Vec::new();
+
+        while position < stack.len() && stack[position] > 0 {
+            let j = stack[position];
+            position += 1;
+            let sibling = sibling_index(j);
+
+            if position < stack.len() && stack[position] == sibling {
+                proof_flags.push(true);
+                position += 1;
+            } else {
+                proof_flags.push(false);
+                proof.push(self.tree[sibling].clone());
+            }
+            stack.push((j - 1) / 2);
+        }
+
+        if tree_indexes.is_empty() {
+            proof.push(self.tree[0].clone());
+        }
+
+        let leaves = leaves_by_tree_index.values().rev().map(|index| self.values[*index].value.clone()).collect();
+
+        Ok(MultiProof { leaves, proof, proof_flags })
+    }
+
+    /// Get the leaf hashes of the values found at the specified indexes, in the multiproof order.
+    pub fn leaf_hashes(&self, value_indexes: &[usize]) -> Vec<String> {
+        let tree_indexes: BTreeSet<usize> =
+            value_indexes.iter().filter_map(|i| self.values.get(*i)).map(|v| v.tree_index).collect();
+        tree_indexes.into_iter().rev().map(|i| self.tree[i].clone()).collect()
+    }
+}
+
+fn sibling_index(index: usize) -> usize {
+    if index.is_multiple_of(2) {
+        index - 1
+    } else {
+        index + 1
+    }
+}
+
+/// Hash a pair of nodes the way OpenZeppelin does, sorting them first
+pub fn hash_pair(a: &[u8], b: &[u8]) -> [u8; 32] {
+    if a <= b {
+        keccak256([a, b].concat())
+    } else {
+        keccak256([b, a].concat())
+    }
+}
+
+/// Decode a `0x` prefixed 32 bytes hex string into a merkle node
+pub fn decode_node(node: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(node.trim_start_matches("0x")).map_err(|_| format!("Invalid hash {}", node))?;
+    bytes.try_into().map_err(|_| format!("Invalid hash length {}", node))
+}
+
+/// Encode a merkle node as a `0x` prefixed hex string
+pub fn encode_node(node: &[u8]) -> String {
+    format!("0x{}", hex::encode(node))
+}
+
+/// Check if a Solidity type can be used inside a leaf
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::merkle::is_supported_type;
+///
+/// assert!(is_supported_type("uint128"));
+/// assert!(is_supported_type("bytes32"));
+/// assert!(is_supported_type("uint64[]"));
+/// assert!(!is_supported_type("uint7"));
+/// assert!(!is_supported_type("tuple"));
+/// assert!(!is_supported_type("string[]"));
+/// ```
+pub fn is_supported_type(kind: &str) -> bool {
+    if let Some(item_kind) = kind.strip_suffix("[]") {
+        // The items of an array are comma separated, so they cannot be dynamic values themselves
+        return !matches!(item_kind, "string" | "bytes") && !item_kind.ends_with("[]") && is_supported_type(item_kind);
+    }
+    match kind {
+        "address" | "bool" | "bytes" | "string" | "uint" | "int" => true,
+        _ => integer_bits(kind).is_some() || fixed_bytes_length(kind).is_some(),
+    }
+}
+
+/// Get the size in bits of a sized integer type such as `uint128` or `int40`
+fn integer_bits(kind: &str) -> Option<usize> {
+    let bits = kind.strip_prefix("uint").or_else(|| kind.strip_prefix("int"))?.parse::<usize>().ok()?;
+    (bits > 0 && bits <= 256 && bits % 8 == 0).then_some(bits)
+}
+
+/// Get the length of a fixed size bytes type such as `bytes32`
+fn fixed_bytes_length(kind: &str) -> Option<usize> {
+    let length = kind.strip_prefix("bytes")?.parse::<usize>().ok()?;
+    (length > 0 && length <= 32).then_some(length)
+}
+
+/// ABI encode a value of the specified Solidity type, checking that it fits the type. The values of the array types
+/// are written as comma separated items between square brackets, e.g. `[1,2,3]`.
+pub fn encode_value(value: &str, kind: &str) -> Result<Token, String> {
+    if let Some(item_kind) = kind.strip_suffix("[]") {
+        let items = value
+            .trim()
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .ok_or_else(|| format!("Invalid array {}", value))?;
+        if items.trim().is_empty() {
+            return Ok(Token::Array(Vec::new()));
+        }
+        let tokens = items.split(',').map(|item| encode_value(item.trim(), item_kind)).collect::<Result<_, _>>()?;
+        return Ok(Token::Array(tokens));
+    }
+
+    let token = match kind {
+        "address" => Token::Address(value.parse::<Address>().map_err(|_| format!("Invalid address {}", value))?),
+        "bool" => match value {
+            "true" => Token::Bool(true),
+            "false" => Token::Bool(false),
+            _ => return Err(format!("Invalid boolean {}", value)),
+        },
+        "string" => Token::String(value.to_string()),
+        "bytes" => Token::Bytes(decode_hex(value)?),
+        _ if kind.starts_with("uint") => {
+            let bits =
+                if kind == "uint" { 256 } else { integer_bits(kind).ok_or(format!("Unsupported type {}", kind))? };
+            let number = U256::from_dec_str(value).map_err(|_| format!("Invalid unsigned integer {}", value))?;
+            if number.bits() > bits {
+                return Err(format!("The value {} does not fit into {}", value, kind));
+            }
+            Token::Uint(number)
+        }
+        _ if kind.starts_with("int") => {
+            let bits =
+                if kind == "int" { 256 } else { integer_bits(kind).ok_or(format!("Unsupported type {}", kind))? };
+            let number = I256::from_dec_str(value).map_err(|_| format!("Invalid integer {}", value))?;
+            if number.bits() as usize > bits {
+                return Err(format!("The value {} does not fit into {}", value, kind));
+            }
+            Token::Int(number.into_raw())
+        }
+        _ if kind.starts_with("bytes") => {
+            let length = fixed_bytes_length(kind).ok_or(format!("Unsupported type {}", kind))?;
+            let bytes = decode_hex(value)?;
+            if bytes.len() != length {
+                return Err(format!("The value {} should contain {} bytes", value, length));
+            }
+            Token::FixedBytes(bytes)
+        }
+        _ => return Err(format!("Unsupported leaf type {}", kind)),
+    };
+    Ok(token)
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+    let hex_value = value.strip_prefix("0x").ok_or_else(|| format!("Invalid hex value {}", value))?;
+    hex::decode(hex_value).map_err(|_| format!("Invalid hex value {}", value))
+}
+
+/// Compute the `standard-v1` leaf hash of the values, i.e. the double keccak256 of their ABI encoding.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::merkle::{encode_node, leaf_hash};
+///
+/// let values = vec!["0".to_string(), "0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491".to_string(), "5000".to_string()];
+/// let types = vec!["uint".to_string(), "address".to_string(), "uint256".to_string()];
+/// let hash = leaf_hash(&values, &types).unwrap();
+/// assert_eq!(encode_node(&hash), "0x23bb7a869a407bc69b27975acff039dfe6a6abe5e3da626e98623d70137eb320");
+/// ```
+pub fn leaf_hash(values: &[String], types: &[String]) -> Result<[u8; 32], String> {
+    if values.len() != types.len() {
+        return Err(format!("Expected {} values but received {}", types.len(), values.len()));
+    }
+
+    let mut tokens = Vec::with_capacity(values.len());
+    for (value, kind) in values.iter().zip(types) {
+        tokens.push(encode_value(value, kind)?);
+    }
+
+    Ok(keccak256(keccak256(abi::encode(&tokens))))
+}
+
+/// Compute the root implied by a proof and the hash of its leaf, following `MerkleProof.processProof`.
+pub fn process_proof(leaf: [u8; 32], proof: &[String]) -> Result<String, String> {
+    let mut computed = leaf;
+    for node in proof {
+        computed = hash_pair(&computed, &decode_node(node)?);
+    }
+    Ok(encode_node(&computed))
+}
+
+/// Compute the root implied by a multiproof and the hashes of its leaves, following `MerkleProof.processMultiProof`.
+/// Flags reading past the available hashes, or leaving proof nodes unused, are reported as an invalid multiproof.
+pub fn process_multi_proof(leaves: &[String], proof: &[String], proof_flags: &[bool]) -> Result<String, String> {
+    if leaves.len() + proof.len() != proof_flags.len() + 1 {
+        return Err(String::from("The provided leaves and proof are not compatible"));
+    }
+
+    let mut hashes: Vec<[u8; 32]> = Vec::with_capacity(leaves.len() + proof_flags.len());
+    for leaf in leaves {
+        hashes.push(decode_node(leaf)?);
+    }
+    let proof = proof.iter().map(|p| decode_node(p)).collect::<Result<Vec<[u8; 32]>, String>>()?;
+
+    let mut hash_position = 0;
+    let mut proof_position = 0;
+    for flag in proof_flags {
+        let a = *hashes.get(hash_position).ok_or("Invalid multiproof format")?;
+        hash_position += 1;
+        let b = if *flag {
+            let b = *hashes.get(hash_position).ok_or("Invalid multiproof format")?;
+            hash_position += 1;
+            b
+        } else {
+            let b = *proof.get(proof_position).ok_or("Invalid multiproof format")?;
+            proof_position += 1;
+            b
+        };
+        hashes.push(hash_pair(&a, &b));
+    }
+
+    if !proof_flags.is_empty() && proof_position != proof.len() {
+        return Err(String::from("Invalid multiproof format"));
+    }
+
+    let root = match (proof_flags.is_empty(), leaves.is_empty()) {
+        (false, _) => *hashes.last().unwrap(),
+        (true, false) => hashes[0],
+        (true, true) => proof[0],
+    };
+    Ok(encode_node(&root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merkle_tree_rs::standard::StandardMerkleTree;
+
+    fn create_dump(size: usize) -> MerkleTreeDump {
+        let values =
+            (0..size).map(|i| vec![i.to_string(), format!("0x{:040x}", i + 1), (100 * (i + 1)).to_string()]).collect();
+        let tree = StandardMerkleTree::of(values, &["uint".to_string(), "address".to_string(), "uint256".to_string()]);
+        serde_json::from_value(serde_json::json!(tree.dump())).unwrap()
+    }
+
+    #[test]
+    fn multi_proof_implies_root() {
+        let dump = create_dump(7);
+        let cases: Vec<Vec<usize>> = vec![vec![], vec![0], vec![0, 1], vec![1, 3, 4, 5], vec![0, 1, 2, 3, 4, 5, 6]];
+
+        for indexes in cases {
+            let multi_proof = dump.multi_proof(&indexes).unwrap();
+            let leaves = dump.leaf_hashes(&indexes);
+            let root = process_multi_proof(&leaves, &multi_proof.proof, &multi_proof.proof_flags).unwrap();
+
+            assert_eq!(multi_proof.leaves.len(), leaves.len());
+            assert_eq!(root, dump.tree[0]);
+        }
+    }
+
+    #[test]
+    fn malformed_multi_proof_is_an_error() {
+        let dump = create_dump(4);
+        let leaves = dump.leaf_hashes(&[0]);
+        let proof = vec![dump.tree[1].clone(), dump.tree[2].clone()];
+
+        assert_eq!(process_multi_proof(&leaves, &proof, &[true, true]).unwrap_err(), "Invalid multiproof format");
+        assert_eq!(process_multi_proof(&leaves, &proof, &[false, true]).unwrap_err(), "Invalid multiproof format");
+    }
+
+    #[test]
+    fn proof_implies_root() {
+        let dump = create_dump(5);
+        let tree = StandardMerkleTree::load(serde_json::from_value(serde_json::json!(dump)).unwrap());
+
+        for (index, value) in dump.values.iter().enumerate() {
+            let proof = tree.get_proof(merkle_tree_rs::standard::LeafType::Number(index));
+            let leaf = leaf_hash(&value.value, &dump.leaf_encoding).unwrap();
+
+            assert_eq!(process_proof(leaf, &proof).unwrap(), dump.tree[0]);
+        }
+    }
+
+    #[test]
+    fn leaf_hash_invalid_values() {
+        let types = vec!["uint".to_string(), "address".to_string()];
+        assert!(leaf_hash(&["1".to_string(), "0xinvalid".to_string()], &types).is_err());
+        assert!(
+            leaf_hash(&["-1".to_string(), "0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491".to_string()], &types).is_err()
+        );
+        assert!(leaf_hash(&["1".to_string()], &types).is_err());
+    }
+
+    #[test]
+    fn build_matches_standard_merkle_tree() {
+        let types = vec!["uint".to_string(), "address".to_string(), "uint256".to_string()];
+        for size in [1, 2, 3, 7, 16] {
+            let expected = create_dump(size);
+            let values = expected.values.iter().map(|v| v.value.clone()).collect();
+            let dump = MerkleTreeDump::build(values, types.clone()).unwrap();
+
+            assert_eq!(dump.tree, expected.tree);
+            for (value, expected_value) in dump.values.iter().zip(&expected.values) {
+                assert_eq!(value.tree_index, expected_value.tree_index);
+            }
+            for index in 0..size {
+                let leaf = leaf_hash(&dump.values[index].value, &types).unwrap();
+                assert_eq!(process_proof(leaf, &dump.proof(index).unwrap()).unwrap(), dump.root());
+            }
+        }
+    }
+
+    #[test]
+    fn build_reports_the_invalid_value() {
+        let types = vec!["uint8".to_string(), "address".to_string()];
+        let values = vec![
+            vec!["255".to_string(), "0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491".to_string()],
+            vec!["256".to_string(), "0x9ad7cad4f10d0c3f875b8a2fd292590490c9f491".to_string()],
+        ];
+
+        let error = MerkleTreeDump::build(values, types).unwrap_err();
+        assert_eq!(error, (1, String::from("The value 256 does not fit into uint8")));
+    }
+
+    #[test]
+    fn encode_value_of_arrays() {
+        let token = encode_value("[1, 2,3]", "uint64[]").unwrap();
+        assert_eq!(token, Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into()), Token::Uint(3.into())]));
+        assert_eq!(encode_value("[]", "uint64[]").unwrap(), Token::Array(Vec::new()));
+        assert!(encode_value("1,2", "uint64[]").is_err());
+        assert!(encode_value("[1,-2]", "uint64[]").is_err());
+    }
+
+    #[test]
+    fn encode_value_checks_the_type() {
+        assert!(encode_value("-128", "int8").is_ok());
+        assert!(encode_value("-129", "int8").is_err());
+        assert!(encode_value("true", "bool").is_ok());
+        assert!(encode_value("0x0102", "bytes2").is_ok());
+        assert!(encode_value("0x0102", "bytes3").is_err());
+        assert!(encode_value("1", "uint7").is_err());
+    }
+
+    #[test]
+    fn multi_proof_out_of_range() {
+        let dump = create_dump(3);
+        assert!(dump.multi_proof(&[3]).is_err());
+    }
+
+    #[test]
+    fn multi_proof_duplicated_index() {
+        let dump = create_dump(3);
+        assert_eq!(dump.multi_proof(&[0, 2, 0]).unwrap_err(), "Cannot prove duplicated index 0");
+    }
+}
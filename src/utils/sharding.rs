@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    data_objects::dto::{CampaignShardDto, RecipientDto, ShardRecipientDto, CAMPAIGN_SCHEMA_VERSION},
+    utils::merkle::MerkleTreeDump,
+};
+
+/// Value of the `layout` member of a sharded campaign manifest
+pub const SHARDED_LAYOUT: &str = "sharded";
+
+/// Average number of recipients aimed for inside a shard
+pub const SHARD_TARGET_SIZE: usize = 4096;
+
+/// Maximum number of address hex characters used to key the shards, i.e. at most 65536 shards
+pub const MAX_PREFIX_LENGTH: usize = 4;
+
+/// Layout of the files of a campaign inside the campaign store
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CampaignLayout {
+    /// A single file containing the recipients and the merkle tree
+    Single,
+    /// A manifest pointing to shards that contain the recipients and their proofs
+    Sharded,
+}
+
+impl CampaignLayout {
+    /// Parse the layout provided through the query parameters, defaulting to a single file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sablier_merkle_api::utils::sharding::CampaignLayout;
+    ///
+    /// assert_eq!(CampaignLayout::parse(""), Ok(CampaignLayout::Single));
+    /// assert_eq!(CampaignLayout::parse("sharded"), Ok(CampaignLayout::Sharded));
+    /// assert!(CampaignLayout::parse("zip").is_err());
+    /// ```
+    pub fn parse(layout: &str) -> Result<CampaignLayout, String> {
+        match layout.trim().to_lowercase().as_str() {
+            "" | "single" => Ok(CampaignLayout::Single),
+            SHARDED_LAYOUT => Ok(CampaignLayout::Sharded),
+            _ => Err(format!("Unknown campaign layout {}. Expected `single` or `sharded`", layout)),
+        }
+    }
+}
+
+/// Get the number of address hex characters needed to keep the shards around `SHARD_TARGET_SIZE` recipients.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::sharding::prefix_length_for;
+///
+/// assert_eq!(prefix_length_for(1000), 0);
+/// assert_eq!(prefix_length_for(50_000), 1);
+/// assert_eq!(prefix_length_for(1_000_000), 2);
+/// ```
+pub fn prefix_length_for(number_of_recipients: usize) -> usize {
+    let mut prefix_length = 0;
+    while prefix_length < MAX_PREFIX_LENGTH && number_of_recipients > SHARD_TARGET_SIZE << (4 * prefix_length) {
+        prefix_length += 1;
+    }
+    prefix_length
+}
+
+/// Get the key of the shard holding the address, i.e. the first hex characters of the lowercase address.
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::sharding::shard_key;
+///
+/// assert_eq!(shard_key(" 0x9AD7CAD4F10D0c3f875b8a2fd292590490c9f491", 2), "9a");
+/// assert_eq!(shard_key("0x9ad7CAD4F10D0c3f875b8a2fd292590490c9f491", 0), "");
+/// ```
+pub fn shard_key(address: &str, prefix_length: usize) -> String {
+    let address = address.trim().to_lowercase();
+    address.trim_start_matches("0x").chars().take(prefix_length).collect()
+}
+
+/// Split the recipients into shards, attaching to each recipient its leaf and its proof inside the merkle tree. The
+/// recipient at index `i` is expected to be the value at index `i` of the tree.
+pub fn build_shards(
+    recipients: &[RecipientDto],
+    tree: &MerkleTreeDump,
+    prefix_length: usize,
+) -> Result<BTreeMap<String, CampaignShardDto>, String> {
+    let root = tree.root();
+    let mut shards: BTreeMap<String, CampaignShardDto> = BTreeMap::new();
+
+    for (index, recipient) in recipients.iter().enumerate() {
+        let shard = shards.entry(shard_key(&recipient.address, prefix_length)).or_insert_with(|| CampaignShardDto {
+            version: CAMPAIGN_SCHEMA_VERSION,
+            root: root.clone(),
+            recipients: Vec::new(),
+        });
+        shard.recipients.push(ShardRecipientDto {
+            index,
+            address: recipient.address.clone(),
+            amount: recipient.amount.clone(),
+            proof: tree.proof(index)?,
+            leaf: tree.values[index].value.clone(),
+        });
+    }
+
+    Ok(shards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::merkle::{leaf_hash, process_proof};
+
+    #[test]
+    fn build_shards_with_valid_proofs() {
+        let recipients: Vec<RecipientDto> = (1..=40)
+            .map(|i| RecipientDto { address: format!("0x{:x}{:039x}", i % 16, i), amount: (i * 10).to_string() })
+            .collect();
+        let leaves =
+            recipients.iter().enumerate().map(|(i, r)| vec![i.to_string(), r.address.clone(), r.amount.clone()]);
+        let types = vec!["uint".to_string(), "address".to_string(), "uint256".to_string()];
+        let tree = MerkleTreeDump::build(leaves.collect(), types.clone()).unwrap();
+
+        let shards = build_shards(&recipients, &tree, 1).unwrap();
+        assert_eq!(shards.len(), 16);
+        assert_eq!(shards.values().map(|s| s.recipients.len()).sum::<usize>(), 40);
+
+        for (key, shard) in shards {
+            assert_eq!(shard.version, CAMPAIGN_SCHEMA_VERSION);
+            for recipient in shard.recipients {
+                assert!(recipient.address.starts_with(&format!("0x{}", key)));
+                let values = vec![recipient.index.to_string(), recipient.address, recipient.amount];
+                assert_eq!(recipient.leaf, values);
+                let leaf = leaf_hash(&values, &types).unwrap();
+                assert_eq!(process_proof(leaf, &recipient.proof).unwrap(), tree.root());
+            }
+        }
+    }
+}
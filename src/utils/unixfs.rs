@@ -0,0 +1,364 @@
+use sha2::{Digest, Sha256};
+use std::error::Error;
+
+/// Size of the chunks a file is split into, matching the default chunker of the IPFS implementations (`size-262144`)
+pub const CHUNK_SIZE: usize = 262_144;
+
+/// Maximum number of links of an intermediary node, matching the default balanced layout of the IPFS implementations
+pub const MAX_LINKS: usize = 174;
+
+const DAG_PB_CODEC: u64 = 0x70;
+const RAW_CODEC: u64 = 0x55;
+const SHA2_256: u8 = 0x12;
+const SHA2_256_LENGTH: u8 = 0x20;
+const UNIXFS_RAW: u64 = 0;
+const UNIXFS_FILE: u64 = 2;
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Version of the CIDs computed for a file. CIDv0 files are built with dag-pb leaves, while CIDv1 files are built with
+/// raw leaves, mirroring what Pinata and Kubo produce for each version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CidVersion {
+    V0,
+    V1,
+}
+
+/// A content addressed block of a UnixFS file
+#[derive(Debug)]
+pub struct Block {
+    pub cid: String,
+    pub data: Vec<u8>,
+}
+
+/// A file encoded as a UnixFS DAG. The blocks are ordered so that the root block is the last one.
+#[derive(Debug)]
+pub struct EncodedFile {
+    pub cid: String,
+    pub blocks: Vec<Block>,
+}
+
+/// A decoded dag-pb node: the CIDs of its links and the `Data` field of its UnixFS payload
+#[derive(Debug)]
+pub struct DecodedNode {
+    pub links: Vec<String>,
+    pub data: Vec<u8>,
+}
+
+/// Field number and value of a length delimited protobuf field
+type ProtobufField<'a> = (u64, &'a [u8]);
+
+/// Node of the DAG while it is being built
+struct Node {
+    cid: Vec<u8>,
+    tsize: u64,
+    filesize: u64,
+}
+
+/// Encode the content as a UnixFS file and compute its CID, using the same chunking and layout as an IPFS node.
+pub fn encode_file(content: &[u8], version: CidVersion) -> EncodedFile {
+    let mut blocks = Vec::new();
+    let mut nodes: Vec<Node> = if content.is_empty() {
+        vec![leaf(&[], UNIXFS_FILE, version, &mut blocks)]
+    } else {
+        // The balanced layout of go-unixfs writes the first leaf as a file and the ones it adds next to it as raw data
+        content
+            .chunks(CHUNK_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| leaf(chunk, if i == 0 { UNIXFS_FILE } else { UNIXFS_RAW }, version, &mut blocks))
+            .collect()
+    };
+
+    while nodes.len() > 1 {
+        nodes = nodes.chunks(MAX_LINKS).map(|children| parent(children, version, &mut blocks)).collect();
+    }
+
+    let root = nodes.remove(0);
+    EncodedFile { cid: cid_to_string(&root.cid), blocks }
+}
+
+/// Compute the CID of the content without keeping its blocks
+///
+/// # Examples
+///
+/// ```
+/// use sablier_merkle_api::utils::unixfs::{compute_cid, CidVersion};
+///
+/// assert_eq!(compute_cid(b"hello world\n", CidVersion::V0), "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o");
+/// ```
+pub fn compute_cid(content: &[u8], version: CidVersion) -> String {
+    encode_file(content, version).cid
+}
+
+/// Build a leaf block for a chunk of the file. The UnixFS type only applies to the dag-pb leaves of CIDv0 files.
+fn leaf(chunk: &[u8], unixfs_type: u64, version: CidVersion, blocks: &mut Vec<Block>) -> Node {
+    let data = match version {
+        CidVersion::V0 => {
+            let mut unixfs = Vec::new();
+            write_varint_field(&mut unixfs, 1, unixfs_type);
+            if !chunk.is_empty() {
+                write_bytes_field(&mut unixfs, 2, chunk);
+            }
+            write_varint_field(&mut unixfs, 3, chunk.len() as u64);
+
+            let mut node = Vec::new();
+            write_bytes_field(&mut node, 1, &unixfs);
+            node
+        }
+        CidVersion::V1 => chunk.to_vec(),
+    };
+    let codec = if version == CidVersion::V0 { DAG_PB_CODEC } else { RAW_CODEC };
+
+    push_block(data, codec, version, chunk.len() as u64, blocks)
+}
+
+/// Build an intermediary dag-pb block linking the children
+fn parent(children: &[Node], version: CidVersion, blocks: &mut Vec<Block>) -> Node {
+    let filesize: u64 = children.iter().map(|c| c.filesize).sum();
+
+    let mut unixfs = Vec::new();
+    write_varint_field(&mut unixfs, 1, UNIXFS_FILE);
+    write_varint_field(&mut unixfs, 3, filesize);
+    for child in children {
+        write_varint_field(&mut unixfs, 4, child.filesize);
+    }
+
+    // The dag-pb canonical form encodes the links before the data
+    let mut node = Vec::new();
+    for child in children {
+        let mut link = Vec::new();
+        write_bytes_field(&mut link, 1, &child.cid);
+        write_bytes_field(&mut link, 2, &[]);
+        write_varint_field(&mut link, 3, child.tsize);
+        write_bytes_field(&mut node, 2, &link);
+    }
+    write_bytes_field(&mut node, 1, &unixfs);
+
+    let mut parent = push_block(node, DAG_PB_CODEC, version, filesize, blocks);
+    parent.tsize += children.iter().map(|c| c.tsize).sum::<u64>();
+    parent
+}
+
+fn push_block(data: Vec<u8>, codec: u64, version: CidVersion, filesize: u64, blocks: &mut Vec<Block>) -> Node {
+    let mut cid = Vec::new();
+    if version == CidVersion::V1 {
+        write_varint(&mut cid, 1);
+        write_varint(&mut cid, codec);
+    }
+    cid.push(SHA2_256);
+    cid.push(SHA2_256_LENGTH);
+    cid.extend_from_slice(&Sha256::digest(&data));
+
+    let node = Node { tsize: data.len() as u64, cid, filesize };
+    blocks.push(Block { cid: cid_to_string(&node.cid), data });
+    node
+}
+
+/// Format the binary CID: base58btc for CIDv0 and multibase base32 for CIDv1
+pub fn cid_to_string(cid: &[u8]) -> String {
+    if is_cid_v0(cid) {
+        return bs58::encode(cid).into_string();
+    }
+    format!("b{}", base32_encode(cid))
+}
+
+/// Parse a CID string into its binary form
+pub fn cid_from_string(cid: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    if cid.len() == 46 && cid.starts_with("Qm") {
+        return Ok(bs58::decode(cid).into_vec()?);
+    }
+    match cid.strip_prefix('b') {
+        Some(encoded) => base32_decode(encoded).ok_or_else(|| format!("Invalid CID {}", cid).into()),
+        None => Err(format!("Unsupported CID {}", cid).into()),
+    }
+}
+
+/// Checks if the block behind the CID is a raw block, in which case it holds the file content itself
+pub fn is_raw(cid: &[u8]) -> bool {
+    !is_cid_v0(cid) && cid.get(1) == Some(&(RAW_CODEC as u8))
+}
+
+fn is_cid_v0(cid: &[u8]) -> bool {
+    cid.len() == 34 && cid[0] == SHA2_256 && cid[1] == SHA2_256_LENGTH
+}
+
+/// Decode a dag-pb block holding a UnixFS file node
+pub fn decode_node(block: &[u8]) -> Result<DecodedNode, Box<dyn Error + Send + Sync>> {
+    let mut links = Vec::new();
+    let mut data = Vec::new();
+
+    for (field, value) in read_fields(block)? {
+        match field {
+            1 => {
+                for (unixfs_field, unixfs_value) in read_fields(value)? {
+                    if unixfs_field == 2 {
+                        data = unixfs_value.to_vec();
+                    }
+                }
+            }
+            2 => {
+                for (link_field, link_value) in read_fields(value)? {
+                    if link_field == 1 {
+                        links.push(cid_to_string(link_value));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(DecodedNode { links, data })
+}
+
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn write_varint_field(buffer: &mut Vec<u8>, field: u64, value: u64) {
+    write_varint(buffer, field << 3);
+    write_varint(buffer, value);
+}
+
+fn write_bytes_field(buffer: &mut Vec<u8>, field: u64, value: &[u8]) {
+    write_varint(buffer, (field << 3) | 2);
+    write_varint(buffer, value.len() as u64);
+    buffer.extend_from_slice(value);
+}
+
+fn read_varint(buffer: &[u8], position: &mut usize) -> Result<u64, Box<dyn Error + Send + Sync>> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *buffer.get(*position).ok_or("Truncated varint")?;
+        *position += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+    Err("Invalid varint".into())
+}
+
+/// Read the length delimited fields of a protobuf message, skipping the varint ones
+fn read_fields(buffer: &[u8]) -> Result<Vec<ProtobufField<'_>>, Box<dyn Error + Send + Sync>> {
+    let mut fields = Vec::new();
+    let mut position = 0;
+
+    while position < buffer.len() {
+        let key = read_varint(buffer, &mut position)?;
+        match key & 7 {
+            0 => {
+                read_varint(buffer, &mut position)?;
+            }
+            2 => {
+                let length = read_varint(buffer, &mut position)? as usize;
+                let end = position.checked_add(length).filter(|end| *end <= buffer.len()).ok_or("Truncated field")?;
+                fields.push((key >> 3, &buffer[position..end]));
+                position = end;
+            }
+            _ => return Err("Unsupported protobuf wire type".into()),
+        }
+    }
+
+    Ok(fields)
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(BASE32_ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        result.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+
+    result
+}
+
+fn base32_decode(data: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(data.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in data.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|a| *a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cid_v0_matches_ipfs() {
+        assert_eq!(compute_cid(b"hello world\n", CidVersion::V0), "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o");
+        assert_eq!(compute_cid(b"", CidVersion::V0), "QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH");
+    }
+
+    #[test]
+    fn cid_v1_matches_ipfs() {
+        assert_eq!(compute_cid(b"", CidVersion::V1), "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku");
+    }
+
+    #[test]
+    fn cid_string_round_trip() {
+        for version in [CidVersion::V0, CidVersion::V1] {
+            let cid = compute_cid(b"hello world\n", version);
+            assert_eq!(cid_to_string(&cid_from_string(&cid).unwrap()), cid);
+        }
+    }
+
+    #[test]
+    fn cid_v0_leaves_follow_the_balanced_layout() {
+        let content: Vec<u8> = (0..CHUNK_SIZE * 2 + 10).map(|i| (i % 251) as u8).collect();
+
+        let encoded = encode_file(&content, CidVersion::V0);
+        let leaves: Vec<&Block> =
+            encoded.blocks.iter().filter(|b| decode_node(&b.data).unwrap().links.is_empty()).collect();
+
+        // The UnixFS payload of a leaf starts with its type: `08 02` for a file and `08 00` for raw data
+        let types: Vec<u64> = leaves
+            .iter()
+            .map(|leaf| {
+                let (_, unixfs) = read_fields(&leaf.data).unwrap().into_iter().find(|(field, _)| *field == 1).unwrap();
+                read_varint(unixfs, &mut 1).unwrap()
+            })
+            .collect();
+        assert_eq!(types, vec![UNIXFS_FILE, UNIXFS_RAW, UNIXFS_RAW]);
+        assert_eq!(decode_node(&leaves[1].data).unwrap().data, &content[CHUNK_SIZE..CHUNK_SIZE * 2]);
+        assert_eq!(decode_node(&encoded.blocks.last().unwrap().data).unwrap().links.len(), 3);
+    }
+
+    #[test]
+    fn large_file_layout() {
+        let content = vec![7u8; CHUNK_SIZE * (MAX_LINKS + 1) + 1];
+
+        let encoded = encode_file(&content, CidVersion::V1);
+        let root = encoded.blocks.last().unwrap();
+        let decoded = decode_node(&root.data).unwrap();
+
+        // Two levels: one full node with 174 chunks and one node with the 2 remaining chunks
+        assert_eq!(root.cid, encoded.cid);
+        assert_eq!(decoded.links.len(), 2);
+        assert!(!is_raw(&cid_from_string(&encoded.cid).unwrap()));
+        assert_eq!(encoded.blocks.iter().filter(|b| b.data.len() == CHUNK_SIZE).count(), MAX_LINKS + 1);
+    }
+}